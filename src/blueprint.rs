@@ -314,6 +314,11 @@ pub fn generate_substations(
 /// * `base_y` - Base Y coordinate for placement.
 /// * `max_rows_per_group` - Maximum rows per group.
 /// * `grayscale_bits` - Number of grayscale bits (affects extra combinators).
+/// * `pulse_once` - When true, each frame's decider only matches `signal-T` equal to the
+///   exact first tick of its window instead of the whole window, emitting its outputs as a
+///   single-tick pulse. Used for delta-encoded groups, where the self-feeding accumulator
+///   (not this decider) is what holds the value steady for the rest of the window — without
+///   this, the accumulator would add the same delta once per tick instead of once per frame.
 ///
 /// # Returns
 ///
@@ -327,6 +332,7 @@ pub fn generate_frame_combinators(
     base_y: f64,
     max_rows_per_group: u32,
     grayscale_bits: u32,
+    pulse_once: bool,
 ) -> (Vec<Entity>, Vec<Wire>, u32) {
     let mut current_entity_number = base_entity_number;
     let num_frames = frame_outputs.len();
@@ -376,7 +382,7 @@ pub fn generate_frame_combinators(
                 arithmetic_conditions: ArithmeticConditions {
                     first_signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_EACH.to_string()), quality: None },
                     second_signal: None,
-                    second_constant: Some(if grayscale_bits == 1 { 1 } else if grayscale_bits == 4 { 15 } else { 255 }),
+                    second_constant: Some((1i32 << grayscale_bits) - 1),
                     operation: OPERATION_AND,
                     output_signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_EACH.to_string()), quality: None },
                 },
@@ -423,7 +429,30 @@ pub fn generate_frame_combinators(
         }
         let decider_num = current_entity_number + 1;
         let lower_bound = (i as u32 * ticks_per_group) as i32;
-        let upper_bound = ((i as u32 + 1) * ticks_per_group) as i32;
+        let conditions = if pulse_once {
+            vec![Condition {
+                first_signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_T.to_string()), quality: None },
+                constant: lower_bound,
+                comparator: COMPARATOR_EQUALS,
+                compare_type: None,
+            }]
+        } else {
+            let upper_bound = ((i as u32 + 1) * ticks_per_group) as i32;
+            vec![
+                Condition {
+                    first_signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_T.to_string()), quality: None },
+                    constant: lower_bound,
+                    comparator: COMPARATOR_GREATER_EQUAL,
+                    compare_type: None,
+                },
+                Condition {
+                    first_signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_T.to_string()), quality: None },
+                    constant: upper_bound,
+                    comparator: COMPARATOR_LESS,
+                    compare_type: Some(COMPARE_AND),
+                },
+            ]
+        };
         let decider_entity = Entity::new(
             decider_num,
             DECIDER_COMBINATOR,
@@ -435,20 +464,7 @@ pub fn generate_frame_combinators(
         .with_direction(DIRECTION_RIGHT)
         .with_control_behavior(ControlBehavior::Decider {
             decider_conditions: DeciderConditions {
-                conditions: vec![
-                    Condition {
-                        first_signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_T.to_string()), quality: None },
-                        constant: lower_bound,
-                        comparator: COMPARATOR_GREATER_EQUAL,
-                        compare_type: None,
-                    },
-                    Condition {
-                        first_signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_T.to_string()), quality: None },
-                        constant: upper_bound,
-                        comparator: COMPARATOR_LESS,
-                        compare_type: Some(COMPARE_AND),
-                    },
-                ],
+                conditions,
                 outputs: outputs.clone(), // Cloning the outputs once per entity.
             },
         });
@@ -564,6 +580,227 @@ pub fn generate_lamps(
     (lamp_entities, lamp_wires, current_entity, top_right_lamp)
 }
 
+/// Decomposes a packed output value into three 8-bit lanes, as if it were a `0xRRGGBB`
+/// pixel. This is the unit `delta_threshold` is measured in: for full-color outputs the
+/// lanes really are the red/green/blue channels, and for other packed modes (grayscale,
+/// YCbCr, palette index) it's a cheap stand-in that still rejects small, probably-
+/// imperceptible changes without requiring a mode-specific comparison.
+fn byte_lanes(value: i32) -> [u8; 3] {
+    [
+        ((value >> 16) & 0xFF) as u8,
+        ((value >> 8) & 0xFF) as u8,
+        (value & 0xFF) as u8,
+    ]
+}
+
+/// Converts a frame's outputs into a residual against what the in-circuit accumulator
+/// currently holds — NOT the raw previous frame, since a dropped (within-threshold) delta
+/// means the accumulator never advanced for that signal.
+///
+/// Only pixels whose value changed by more than `delta_threshold` (per byte lane, see
+/// `byte_lanes`) are kept, and `constant` holds the signed delta (`new - held`) rather
+/// than the absolute value, so a self-feeding accumulator combinator can reconstruct the
+/// true value in-circuit via `S := S + delta`. Pixels whose change falls within the
+/// threshold are left alone, so the accumulator keeps showing its last *held* value.
+///
+/// # Arguments
+///
+/// * `held` - What the accumulator currently holds for each signal (same length/order
+///   as `current`) — the raw value of the last frame whose delta actually got emitted,
+///   not necessarily the immediately preceding frame.
+/// * `current` - Outputs for the frame being encoded.
+/// * `delta_threshold` - Maximum per-lane change to ignore (0 keeps only exact matches).
+///
+/// # Returns
+///
+/// A tuple of the residual outputs (only the changed signals, as deltas) and the updated
+/// `held` values to carry into the next frame.
+fn outputs_to_residual(held: &[i32], current: &[CombinatorOutput], delta_threshold: u8) -> (Vec<CombinatorOutput>, Vec<i32>) {
+    let mut updated_held = held.to_vec();
+    let residual = held
+        .iter()
+        .zip(current.iter())
+        .enumerate()
+        .filter_map(|(idx, (&held_val, curr))| {
+            let curr_val = curr.constant.unwrap_or(0);
+            if held_val == curr_val {
+                return None;
+            }
+            if delta_threshold > 0 {
+                let held_lanes = byte_lanes(held_val);
+                let curr_lanes = byte_lanes(curr_val);
+                let within_threshold = held_lanes
+                    .iter()
+                    .zip(curr_lanes.iter())
+                    .all(|(&p, &c)| (p as i32 - c as i32).abs() <= delta_threshold as i32);
+                if within_threshold {
+                    return None;
+                }
+            }
+            updated_held[idx] = curr_val;
+            Some(CombinatorOutput {
+                copy_count_from_input: false,
+                constant: Some(curr_val - held_val),
+                signal: curr.signal.clone(),
+            })
+        })
+        .collect();
+    (residual, updated_held)
+}
+
+/// Rewrites a group's per-frame outputs into delta-encoded form.
+///
+/// Frame 0 is always emitted in full as the loop's keyframe (so the animation
+/// reconciles perfectly on wrap-around), and subsequent frames are replaced by
+/// their residual against the previous *keyframe-relative* frame. A keyframe is also
+/// forced every `keyframe_interval` frames (0 disables the periodic check) and whenever
+/// the running residual size for the group would exceed `DELTA_KEYFRAME_WIRE_BUDGET`, to
+/// bound how long drift can accumulate.
+///
+/// Every keyframe's outputs (frame 0 and any interior one) also carry `signal-K = 1`, so
+/// the accumulator's feedback gate (see `generate_delta_accumulator`) can tell, purely from
+/// what's on the wire that tick, that this frame's absolute value should replace the
+/// running total rather than add onto it — the condition isn't limited to frame 0.
+///
+/// # Arguments
+///
+/// * `frame_outputs` - Full per-frame outputs, one entry per frame in the group.
+/// * `keyframe_interval` - Force a keyframe every N frames (0 to rely solely on the wire budget).
+/// * `delta_threshold` - Per-lane change threshold passed through to `outputs_to_residual`.
+///
+/// # Returns
+///
+/// A tuple of the delta-encoded outputs (keyframes tagged with `signal-K`) and the indices
+/// that were kept as keyframes.
+fn delta_encode_group(frame_outputs: &[Vec<CombinatorOutput>], keyframe_interval: u32, delta_threshold: u8) -> (Vec<Vec<CombinatorOutput>>, HashSet<usize>) {
+    let mut encoded = Vec::with_capacity(frame_outputs.len());
+    let mut keyframes = HashSet::new();
+    let mut running_wire_count = 0usize;
+    let mut held: Option<Vec<i32>> = None;
+
+    for (i, outputs) in frame_outputs.iter().enumerate() {
+        let periodic_keyframe = keyframe_interval > 0 && i % keyframe_interval as usize == 0;
+        let residual = match &held {
+            Some(held_vals) if i != 0 && !periodic_keyframe => Some(outputs_to_residual(held_vals, outputs, delta_threshold)),
+            _ => None,
+        };
+        let is_keyframe = match &residual {
+            Some((residual, _)) => running_wire_count + residual.len() > DELTA_KEYFRAME_WIRE_BUDGET,
+            None => true,
+        };
+        if is_keyframe {
+            keyframes.insert(i);
+            running_wire_count = outputs.len();
+            let mut keyframe_outputs = outputs.clone();
+            keyframe_outputs.push(CombinatorOutput {
+                copy_count_from_input: false,
+                constant: Some(1),
+                signal: Arc::from(Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_K.to_string()), quality: None }),
+            });
+            encoded.push(keyframe_outputs);
+            held = Some(outputs.iter().map(|o| o.constant.unwrap_or(0)).collect());
+        } else {
+            let (residual, updated_held) = residual.unwrap();
+            running_wire_count += residual.len();
+            encoded.push(residual);
+            held = Some(updated_held);
+        }
+    }
+    (encoded, keyframes)
+}
+
+/// Generates a self-feeding accumulator ("register") circuit for a delta-encoded group.
+///
+/// Three combinators work together so the accumulator can be reset on an arbitrary,
+/// data-dependent set of keyframe ticks instead of just frame 0's window:
+///
+/// * `accumulator` (decider, always-true condition) unconditionally echoes `signal-each`,
+///   re-emitting the sum of last tick's total and whatever lands on the wire this tick
+///   (`S := S + delta`).
+/// * `hold_gate` (decider) watches `signal-K`, the reset marker `delta_encode_group` embeds
+///   into every keyframe's payload, and outputs `signal-H = 1` on every tick except one
+///   where `signal-K` is present.
+/// * `feedback` (arithmetic) multiplies the accumulator's output by `signal-H` before wiring
+///   it back into the accumulator's input, so on a reset tick the stale total is zeroed out
+///   and the keyframe's fresh absolute value (arriving the same tick on the external wire)
+///   becomes the new total instead of being added onto it.
+///
+/// # Arguments
+///
+/// * `entity_number` - Entity number for the first (accumulator) combinator; `hold_gate`
+///   and `feedback` follow it.
+/// * `position` - Placement for the accumulator; `hold_gate` and `feedback` are placed one
+///   and two tiles to its right respectively.
+///
+/// # Returns
+///
+/// The three entities, the wires connecting them into the feedback loop, and the
+/// accumulator's entity number (the one downstream code reads the running total from).
+fn generate_delta_accumulator(entity_number: u32, position: Position) -> (Vec<Entity>, Vec<Wire>, u32) {
+    let accumulator_number = entity_number;
+    let hold_gate_number = entity_number + 1;
+    let feedback_number = entity_number + 2;
+
+    let accumulator = Entity::new(accumulator_number, DECIDER_COMBINATOR, Position { x: position.x, y: position.y })
+        .with_direction(DIRECTION_RIGHT)
+        .with_control_behavior(ControlBehavior::Decider {
+            decider_conditions: DeciderConditions {
+                conditions: vec![Condition {
+                    first_signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_T.to_string()), quality: None },
+                    constant: 0,
+                    comparator: COMPARATOR_GREATER_EQUAL,
+                    compare_type: None,
+                }],
+                outputs: vec![CombinatorOutput {
+                    copy_count_from_input: true,
+                    constant: None,
+                    signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_EACH.to_string()), quality: None },
+                }],
+            },
+        })
+        .with_description("Residual accumulator: S := S + delta every tick, so the lamps see the running total rather than the raw per-frame decider output. Always true; `feedback` is what actually gates the reset.");
+
+    let hold_gate = Entity::new(hold_gate_number, DECIDER_COMBINATOR, Position { x: position.x + 1.0, y: position.y })
+        .with_direction(DIRECTION_RIGHT)
+        .with_control_behavior(ControlBehavior::Decider {
+            decider_conditions: DeciderConditions {
+                conditions: vec![Condition {
+                    first_signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_K.to_string()), quality: None },
+                    constant: 1,
+                    comparator: COMPARATOR_LESS,
+                    compare_type: None,
+                }],
+                outputs: vec![CombinatorOutput {
+                    copy_count_from_input: false,
+                    constant: Some(1),
+                    signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_H.to_string()), quality: None },
+                }],
+            },
+        })
+        .with_description("Emits signal-H = 1 on every tick except a keyframe's reset pulse (signal-K present), so `feedback` knows when to let the stale accumulator total through.");
+
+    let feedback = Entity::new(feedback_number, ARITHMETIC_COMBINATOR, Position { x: position.x + 2.0, y: position.y })
+        .with_direction(DIRECTION_RIGHT)
+        .with_control_behavior(ControlBehavior::Arithmetic {
+            arithmetic_conditions: ArithmeticConditions {
+                first_signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_EACH.to_string()), quality: None },
+                second_signal: Some(Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_H.to_string()), quality: None }),
+                second_constant: None,
+                operation: OPERATION_MUL,
+                output_signal: Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_EACH.to_string()), quality: None },
+            },
+        })
+        .with_description("Zeroes the accumulator's fed-back total on a reset tick (signal-H = 0) so a keyframe's absolute value replaces it instead of adding onto it.");
+
+    let wires = vec![
+        [accumulator_number, 2, feedback_number, 1],
+        [hold_gate_number, 2, feedback_number, 3],
+        [feedback_number, 2, accumulator_number, 1],
+    ];
+
+    (vec![accumulator, hold_gate, feedback], wires, accumulator_number)
+}
+
 /// Builds the complete blueprint JSON by combining all components.
 ///
 /// # Arguments
@@ -574,6 +811,28 @@ pub fn generate_lamps(
 /// * `grayscale_bits` - Number of grayscale bits (0 means color mode).
 /// * `signals` - Available signals vector.
 /// * `substation_quality` - Quality level for substations.
+/// * `delta_encode` - Whether to residual-encode frames through a self-feeding accumulator.
+///   Only supported in plain grayscale/full-color mode; combining it with `color_bits`,
+///   `ycbcr_bits`, or `palette_bits` returns an error since the accumulator only reconstructs
+///   a single packed sample, not those chains' decoded output.
+/// * `delta_keyframe_interval` - Force a keyframe every N frames when delta-encoding
+///   (0 to rely solely on the `DELTA_KEYFRAME_WIRE_BUDGET` cap).
+/// * `delta_threshold` - Per-channel change threshold at or below which a delta-encoded pixel
+///   is left at its last value instead of being updated (0 keeps only exact matches).
+/// * `color_bits` - Total bits per packed color pixel (8/12/16 for RGB332/444/565, 0 for full color).
+/// * `ycbcr_bits` - Bits to pack each Y/Cb/Cr sample to in YCbCr 4:2:0 mode (0 disables it,
+///   1/4/8 are the only other accepted values — see `pack_ycbcr_frames_to_outputs`).
+///   Takes priority over `color_bits` when `grayscale_bits` is also 0.
+/// * `dither_mode` - 0 disables dithering, 1 selects Floyd–Steinberg error diffusion, 2
+///   selects ordered (Bayer matrix) dithering, for the 1-bit/4-bit grayscale and
+///   reduced-depth color paths instead of quantizing each pixel independently.
+/// * `palette_bits` - Bits per packed index for indexed-palette color mode (4/6/8 for a
+///   16/64/256-color palette, 0 disables it). Used only when `grayscale_bits`, `ycbcr_bits`,
+///   and `color_bits` are all 0.
+/// * `max_colors` - Caps the shared palette's actual color count below what `palette_bits`
+///   allows (0 to use the full `1 << palette_bits` palette). The index is still packed at
+///   `palette_bits` wide either way — this only shrinks the median-cut search, e.g. to
+///   guarantee color stability across frames with a smaller, more stable shared palette.
 ///
 /// # Returns
 ///
@@ -584,6 +843,14 @@ pub fn update_full_blueprint(
     use_dlc: bool,
     grayscale_bits: u32,
     substation_quality: String,
+    delta_encode: bool,
+    delta_keyframe_interval: u32,
+    delta_threshold: u8,
+    color_bits: u32,
+    ycbcr_bits: u32,
+    dither_mode: u32,
+    palette_bits: u32,
+    max_colors: u32,
 ) -> Result<Blueprint, JsValue> {
     report_progress(0, "Starting blueprint update");
 
@@ -595,8 +862,31 @@ pub fn update_full_blueprint(
     }
 
     let use_grayscale = grayscale_bits > 0;
+    let use_ycbcr = !use_grayscale && ycbcr_bits > 0;
+    let use_color_packing = !use_grayscale && !use_ycbcr && color_bits > 0;
+    let use_palette = !use_grayscale && !use_ycbcr && !use_color_packing && palette_bits > 0;
+    // The accumulator only reconstructs a single packed signal (the plain grayscale sample);
+    // it doesn't know how to unpack the color/YCbCr/palette chains that sit downstream of the
+    // raw per-frame bits, so combining `delta_encode` with any of them would silently wire the
+    // accumulated total straight to the lamps instead of a decoded color. Reject it up front.
+    if delta_encode && (use_ycbcr || use_color_packing || use_palette) {
+        return Err(JsValue::from_str(
+            "delta_encode cannot be combined with color_bits, ycbcr_bits, or palette_bits; use grayscale_bits or full color instead.",
+        ));
+    }
+    let palette = if use_palette {
+        let full_palette_size = 1u32 << palette_bits;
+        let palette_size = if max_colors > 0 { max_colors.min(full_palette_size) } else { full_palette_size };
+        crate::image_processing::build_palette(&sampled_frames, palette_size)
+    } else {
+        Vec::new()
+    };
+    // The bit-packing, timer, and shifter math is bit-width-agnostic, so color packing,
+    // YCbCr, and palette indices all reuse it wholesale with their own per-sample bit depth
+    // standing in for `grayscale_bits`.
+    let pack_bits = if use_grayscale { grayscale_bits } else if use_ycbcr { ycbcr_bits } else if use_color_packing { color_bits } else if use_palette { palette_bits } else { 0 };
     let total_frames = sampled_frames.len() as u32;
-    let frames_per_combinator = if grayscale_bits > 0 { 32 / grayscale_bits } else { 1 };
+    let frames_per_combinator = if pack_bits > 0 { 32 / pack_bits } else { 1 };
     let (full_width, full_height) = sampled_frames[0].dimensions();
     let max_columns_per_group = ((signals.len() as u32) / full_height).min(full_width);
     let num_groups = (full_width as f64 / max_columns_per_group as f64).ceil() as u32;
@@ -609,7 +899,7 @@ pub fn update_full_blueprint(
 
     let ticks_per_frame = (60.0 / fps as f64) as u32;
     let stop = total_frames * ticks_per_frame;
-    let (timer_entities, timer_wires) = generate_timer(stop, grayscale_bits, ticks_per_frame, frames_per_combinator);
+    let (timer_entities, timer_wires) = generate_timer(stop, pack_bits, ticks_per_frame, frames_per_combinator);
 
     let mut all_entities = timer_entities;
     let mut all_wires: Vec<Wire> = timer_wires;
@@ -625,7 +915,7 @@ pub fn update_full_blueprint(
             substation_quality,
             full_width,
             full_height,
-            max_rows_per_group + if grayscale_bits == 1 || grayscale_bits == 4 { 2 } else if grayscale_bits == 8 { 1 } else { 0 },
+            max_rows_per_group + if pack_bits == 1 || pack_bits == 4 { 2 } else if pack_bits > 0 { 1 } else { 0 },
             next_entity,
         );
 
@@ -648,7 +938,40 @@ pub fn update_full_blueprint(
                         .iter()
                         .map(|frame| frame.crop_imm(group_left, 0, group_width, full_height))
                         .collect();
-                    pack_grayscale_frames_to_outputs(&cropped_frames, signals.clone(), grayscale_bits)
+                    pack_grayscale_frames_to_outputs(&cropped_frames, signals.clone(), grayscale_bits, dither_mode)
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else if use_color_packing {
+            sampled_frames
+                .chunks(frames_per_combinator as usize)
+                .map(|chunk| {
+                    let cropped_frames: Vec<image::DynamicImage> = chunk
+                        .iter()
+                        .map(|frame| frame.crop_imm(group_left, 0, group_width, full_height))
+                        .collect();
+                    pack_color_frames_to_outputs(&cropped_frames, signals.clone(), color_bits, dither_mode)
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else if use_ycbcr {
+            sampled_frames
+                .chunks(frames_per_combinator as usize)
+                .map(|chunk| {
+                    let cropped_frames: Vec<image::DynamicImage> = chunk
+                        .iter()
+                        .map(|frame| frame.crop_imm(group_left, 0, group_width, full_height))
+                        .collect();
+                    pack_ycbcr_frames_to_outputs(&cropped_frames, signals.clone(), ycbcr_bits)
+                })
+                .collect::<Result<Vec<_>, _>>()?
+        } else if use_palette {
+            sampled_frames
+                .chunks(frames_per_combinator as usize)
+                .map(|chunk| {
+                    let cropped_frames: Vec<image::DynamicImage> = chunk
+                        .iter()
+                        .map(|frame| frame.crop_imm(group_left, 0, group_width, full_height))
+                        .collect();
+                    pack_palette_frames_to_outputs(&cropped_frames, signals.clone(), &palette, palette_bits, dither_mode)
                 })
                 .collect::<Result<Vec<_>, _>>()?
         } else {
@@ -660,23 +983,121 @@ pub fn update_full_blueprint(
             outputs
         };
 
+        let group_frames_outputs = if delta_encode {
+            delta_encode_group(&group_frames_outputs, delta_keyframe_interval, delta_threshold).0
+        } else {
+            group_frames_outputs
+        };
+
         let group_offset_x = group_index * max_columns_per_group;
-        let first_connection_entity = if use_grayscale { next_entity } else { next_entity + 1 };
+        let first_connection_entity = if use_grayscale || use_color_packing || use_ycbcr || use_palette { next_entity } else { next_entity + 1 };
         let (group_combinators, mut group_comb_wires, new_next_entity) = generate_frame_combinators(
             &group_frames_outputs,
             &substation_occupied_y,
             ticks_per_frame * frames_per_combinator,
             next_entity,
             group_offset_x as f64 + 0.5,
-            if grayscale_bits == 1 || grayscale_bits == 4 { -5.0 } else if grayscale_bits == 8 { -4.0 } else { -3.0 },
+            if pack_bits == 1 || pack_bits == 4 { -5.0 } else if pack_bits > 0 { -4.0 } else { -3.0 },
             max_rows_per_group,
-            grayscale_bits,
+            pack_bits,
+            delta_encode,
         );
         if group_index == 0 {
             group_comb_wires.push([3, 4, first_connection_entity, 2]);
         }
         next_entity = new_next_entity;
 
+        let accumulator_entity = if delta_encode {
+            let (accumulator_entities, accumulator_wires, accumulator_number) = generate_delta_accumulator(
+                next_entity,
+                Position { x: group_offset_x as f64 + 0.5, y: -2.0 },
+            );
+            let hold_gate_number = accumulator_number + 1;
+            all_entities.extend(accumulator_entities);
+            group_comb_wires.extend(accumulator_wires);
+            group_comb_wires.push([accumulator_number, 1, first_connection_entity, 2]);
+            group_comb_wires.push([hold_gate_number, 1, first_connection_entity, 2]);
+            next_entity += 3;
+            Some(accumulator_number)
+        } else {
+            None
+        };
+
+        let color_unpack_finals = if use_color_packing && accumulator_entity.is_none() {
+            let masked_value_entity = first_connection_entity + 1;
+            let (chain_entities, chain_wires, finals) = generate_color_unpack_chain(
+                next_entity,
+                group_offset_x as f64 + 0.5,
+                -1.0,
+                masked_value_entity,
+                color_bits,
+            );
+            next_entity += chain_entities.len() as u32;
+            all_entities.extend(chain_entities);
+            group_comb_wires.extend(chain_wires);
+            Some(finals)
+        } else {
+            None
+        };
+
+        // One decoder chain per pixel: unlike color packing's `signal-each` chain (which
+        // reconstructs every pixel's R/G/B at once because each pixel already owns its full
+        // packed color signal), YCbCr's chroma samples are shared across a 2x2 block, so each
+        // pixel's reconstruction has to look up its *own* Y signal alongside its *block's*
+        // Cb/Cr signals individually.
+        let ycbcr_finals = if use_ycbcr && accumulator_entity.is_none() {
+            // The BT.601 coefficients in `generate_ycbcr_pixel_decoder` assume a 0..255-ranged
+            // sample, which only the rescaled entity (mirroring grayscale's `last_shifter`)
+            // provides for 1/4-bit; the masked-but-unscaled `+1` entity stays in 0..(2^bits-1).
+            let masked_value_entity = if ycbcr_bits == 1 || ycbcr_bits == 4 { first_connection_entity + 2 } else { first_connection_entity + 1 };
+            let group_width_usize = group_width as usize;
+            let block_width = (group_width_usize + 1) / 2;
+            let num_pixels_in_group = group_width_usize * full_height as usize;
+            let block_height = (full_height as usize + 1) / 2;
+            let num_blocks_in_group = block_width * block_height;
+            let mut finals = Vec::new();
+            for r in 0..full_height as usize {
+                for c in 0..group_width_usize {
+                    let pixel_index = r * group_width_usize + c;
+                    let block_index = (r / 2) * block_width + (c / 2);
+                    let (chain_entities, chain_wires, pixel_finals) = generate_ycbcr_pixel_decoder(
+                        next_entity,
+                        group_offset_x as f64 + 0.5 + c as f64,
+                        -1.0 - r as f64,
+                        masked_value_entity,
+                        &signals[pixel_index],
+                        &signals[num_pixels_in_group + block_index],
+                        &signals[num_pixels_in_group + num_blocks_in_group + block_index],
+                    );
+                    next_entity += chain_entities.len() as u32;
+                    all_entities.extend(chain_entities);
+                    group_comb_wires.extend(chain_wires);
+                    finals.extend(pixel_finals);
+                }
+            }
+            Some(finals)
+        } else {
+            None
+        };
+
+        let palette_finals = if use_palette && accumulator_entity.is_none() {
+            let masked_value_entity = first_connection_entity + 1;
+            let palette_colors: Vec<u32> = palette.iter().map(|&(r, g, b)| rgb_to_int(r, g, b)).collect();
+            let (chain_entities, chain_wires, finals) = generate_palette_lut_decoder(
+                next_entity,
+                group_offset_x as f64 + 0.5,
+                -1.0,
+                masked_value_entity,
+                &palette_colors,
+            );
+            next_entity += chain_entities.len() as u32;
+            all_entities.extend(chain_entities);
+            group_comb_wires.extend(chain_wires);
+            Some(finals)
+        } else {
+            None
+        };
+
         let (group_lamps, mut group_lamp_wires, new_next_entity, top_right_lamp) = generate_lamps(
             signals.clone(),
             group_width,
@@ -690,7 +1111,25 @@ pub fn update_full_blueprint(
         next_entity = new_next_entity;
 
         let first_lamp_entity = group_lamps[0].entity_number;
-        if use_grayscale {
+        if let Some(accumulator_number) = accumulator_entity {
+            group_comb_wires.push([first_lamp_entity, 1, accumulator_number, 1]);
+            group_comb_wires.push([first_lamp_entity, 2, accumulator_number, 2]);
+        } else if let Some(finals) = color_unpack_finals {
+            for final_entity in finals {
+                group_comb_wires.push([first_lamp_entity, 1, final_entity, 4]);
+                group_comb_wires.push([first_lamp_entity, 2, final_entity, 4]);
+            }
+        } else if let Some(finals) = ycbcr_finals {
+            for final_entity in finals {
+                group_comb_wires.push([first_lamp_entity, 1, final_entity, 4]);
+                group_comb_wires.push([first_lamp_entity, 2, final_entity, 4]);
+            }
+        } else if let Some(finals) = palette_finals {
+            for final_entity in finals {
+                group_comb_wires.push([first_lamp_entity, 1, final_entity, 4]);
+                group_comb_wires.push([first_lamp_entity, 2, final_entity, 4]);
+            }
+        } else if use_grayscale {
             group_comb_wires.push([first_lamp_entity, 2, first_connection_entity, 2]);
             let last_shifter = if grayscale_bits == 1 || grayscale_bits == 4 { first_connection_entity + 2 } else { first_connection_entity + 1 };
             group_comb_wires.push([first_lamp_entity, 1, last_shifter, 3]);
@@ -732,13 +1171,13 @@ pub fn update_full_blueprint(
     Ok(blueprint)
 }
 
-/// Converts an RGB pixel to an integer using a utility function.
+/// Converts a full-color frame into one `CombinatorOutput` per pixel, packing each
+/// pixel's RGB value via `rgb_bytes_to_ints` rather than converting pixels one at a time.
 ///
 /// # Arguments
 ///
-/// * `r` - Red channel.
-/// * `g` - Green channel.
-/// * `b` - Blue channel.
+/// * `frame` - The frame to convert.
+/// * `signals` - The signals to map to each pixel.
 ///
 /// # Returns
 ///
@@ -758,12 +1197,10 @@ pub fn frame_to_outputs(
     }
     let rgb_image = frame.to_rgb8();
     let pixels = rgb_image.into_raw();
+    let packed = crate::image_processing::rgb_bytes_to_ints(&pixels);
     let mut outputs = Vec::with_capacity(num_pixels);
-    for (i, chunk) in pixels.chunks(3).enumerate() {
-        if chunk.len() < 3 {
-            continue;
-        }
-        let value = rgb_to_int(chunk[0], chunk[1], chunk[2]) as i32;
+    for (i, &value) in packed.iter().enumerate() {
+        let value = value as i32;
         let signal = Arc::clone(&signals[i]);
         outputs.push(CombinatorOutput {
             copy_count_from_input: false,
@@ -774,6 +1211,113 @@ pub fn frame_to_outputs(
     Ok(outputs)
 }
 
+/// Diffuses quantization error across a row-major luma buffer using Floyd–Steinberg
+/// weights, so banding in the 1-bit/4-bit grayscale paths turns into dither noise.
+///
+/// Each pixel is quantized to the nearest of `levels` evenly spaced steps across the
+/// 0-255 range, and the resulting error (`old - new`) is pushed onto not-yet-visited
+/// neighbors (right 7/16, bottom-left 3/16, bottom 5/16, bottom-right 1/16), skipping
+/// neighbors that fall outside the frame. Only the current and next row ever carry
+/// pending error, so the accumulator is two `width`-long buffers rather than one
+/// `width * height` one, and a fresh call (one per frame) always starts both at zero —
+/// error never bleeds across frames.
+///
+/// # Arguments
+///
+/// * `width` / `height` - Frame dimensions.
+/// * `data` - Row-major samples (e.g. from `GrayImage::as_raw`).
+/// * `levels` - Number of quantization levels (2 for 1-bit, 16 for 4-bit).
+///
+/// # Returns
+///
+/// The quantized level index (`0..levels`) for each pixel, in the same row-major order.
+fn floyd_steinberg_dither(width: u32, height: u32, data: &[u8], levels: u32) -> Vec<u32> {
+    let width = width as usize;
+    let height = height as usize;
+    let step = 255.0 / (levels - 1) as f32;
+    let mut indices = vec![0u32; width * height];
+
+    let mut current_row = vec![0f32; width];
+    let mut next_row = vec![0f32; width];
+
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            let old_value = data[i] as f32 + current_row[x];
+            let level = (old_value / step).round().clamp(0.0, (levels - 1) as f32);
+            indices[i] = level as u32;
+            let err = old_value - level * step;
+
+            if x + 1 < width {
+                current_row[x + 1] += err * 7.0 / 16.0;
+                next_row[x + 1] += err * 1.0 / 16.0;
+            }
+            if x > 0 {
+                next_row[x - 1] += err * 3.0 / 16.0;
+            }
+            next_row[x] += err * 5.0 / 16.0;
+        }
+
+        current_row.copy_from_slice(&next_row);
+        next_row.iter_mut().for_each(|v| *v = 0.0);
+    }
+
+    indices
+}
+
+/// 4x4 Bayer ordered-dither matrix (values 0-15), tiled across the frame. Unlike
+/// Floyd–Steinberg error diffusion, the same offset lands on the same pixel position in
+/// every frame, so looping content gets an identically-tiled dither pattern each pass
+/// instead of one that drifts with the carried-over error.
+const BAYER_MATRIX: [[u8; 4]; 4] = [
+    [0, 8, 2, 10],
+    [12, 4, 14, 6],
+    [3, 11, 1, 9],
+    [15, 7, 13, 5],
+];
+
+/// Quantizes a row-major buffer to `levels` evenly spaced steps using ordered (Bayer
+/// matrix) dithering: each pixel's value is nudged by a fixed, position-dependent offset
+/// before rounding to the nearest level.
+///
+/// # Arguments
+///
+/// * `width` - Frame width (the Bayer pattern tiles row-major off this alone, so unlike
+///   `floyd_steinberg_dither` it needs no `height`).
+/// * `data` - Row-major samples (e.g. from `GrayImage::as_raw`).
+/// * `levels` - Number of quantization levels (2 for 1-bit, 16 for 4-bit).
+///
+/// # Returns
+///
+/// The quantized level index (`0..levels`) for each pixel, in the same row-major order.
+fn bayer_dither(width: u32, data: &[u8], levels: u32) -> Vec<u32> {
+    let width = width as usize;
+    let step = 255.0 / (levels - 1) as f32;
+    data.iter()
+        .enumerate()
+        .map(|(i, &v)| {
+            let x = i % width;
+            let y = i / width;
+            let offset = (BAYER_MATRIX[y % 4][x % 4] as f32 / 16.0 - 0.5) * step;
+            ((v as f32 + offset) / step).round().clamp(0.0, (levels - 1) as f32) as u32
+        })
+        .collect()
+}
+
+/// Dithers a row-major buffer to `levels` evenly spaced steps using the requested mode.
+///
+/// # Arguments
+///
+/// * `dither_mode` - 0 disables dithering, 1 selects Floyd–Steinberg error diffusion,
+///   2 selects ordered (Bayer matrix) dithering.
+fn dither_levels(width: u32, height: u32, data: &[u8], levels: u32, dither_mode: u32) -> Option<Vec<u32>> {
+    match dither_mode {
+        1 => Some(floyd_steinberg_dither(width, height, data, levels)),
+        2 => Some(bayer_dither(width, data, levels)),
+        _ => None,
+    }
+}
+
 /// Packs grayscale frames into output signals by bit-packing pixel values.
 ///
 /// # Arguments
@@ -781,6 +1325,9 @@ pub fn frame_to_outputs(
 /// * `frames` - A slice of grayscale image frames.
 /// * `signals` - The signals to map to each pixel.
 /// * `grayscale_bits` - Number of bits for grayscale conversion.
+/// * `dither_mode` - 0 disables dithering, 1 selects Floyd–Steinberg error diffusion, 2
+///   selects ordered (Bayer matrix) dithering. Only applies to the 1-bit/4-bit paths,
+///   which are the ones coarse enough to band visibly.
 ///
 /// # Returns
 ///
@@ -789,6 +1336,7 @@ pub fn pack_grayscale_frames_to_outputs(
     frames: &[image::DynamicImage],
     signals: Vec<Arc<Signal>>,
     grayscale_bits: u32,
+    dither_mode: u32,
 ) -> Result<Vec<CombinatorOutput>, JsValue> {
     if frames.is_empty() {
         return Err(JsValue::from_str("No frames provided for packing"));
@@ -803,10 +1351,28 @@ pub fn pack_grayscale_frames_to_outputs(
         )));
     }
     let luma_images: Vec<_> = frames.iter().map(|frame| frame.to_luma8()).collect();
+    let levels = if dither_mode > 0 && grayscale_bits == 1 {
+        Some(2)
+    } else if dither_mode > 0 && grayscale_bits == 4 {
+        Some(16)
+    } else {
+        None
+    };
+    let dithered: Vec<Vec<u32>> = match levels {
+        Some(levels) => luma_images
+            .iter()
+            .map(|img| dither_levels(width, height, img.as_raw(), levels, dither_mode).unwrap())
+            .collect(),
+        None => Vec::new(),
+    };
     let mut outputs = Vec::with_capacity(num_pixels);
     for i in 0..num_pixels {
         let mut packed_value = 0;
         for (j, img) in luma_images.iter().enumerate() {
+            if let Some(frame_indices) = dithered.get(j) {
+                packed_value |= frame_indices[i] << (grayscale_bits * j as u32);
+                continue;
+            }
             let pixel_value = img.as_raw()[i];
             if grayscale_bits == 1 {
                 let binary_value = if pixel_value >= GRAYSCALE_THRESHOLD {
@@ -831,3 +1397,701 @@ pub fn pack_grayscale_frames_to_outputs(
     }
     Ok(outputs)
 }
+
+/// Per-channel bit widths for a reduced-depth color mode.
+///
+/// # Arguments
+///
+/// * `color_bits` - Total bits per packed pixel (8 = RGB332, 12 = RGB444, 16 = RGB565).
+///
+/// # Returns
+///
+/// `(red_bits, green_bits, blue_bits)`.
+fn color_channel_bits(color_bits: u32) -> (u32, u32, u32) {
+    match color_bits {
+        12 => (4, 4, 4),
+        16 => (5, 6, 5),
+        _ => (3, 3, 2), // RGB332, and the default.
+    }
+}
+
+/// Extracts one channel from an interleaved RGB buffer into its own contiguous buffer,
+/// so it can be fed through `floyd_steinberg_dither` independently of the other two.
+fn extract_channel(rgb: &[u8], channel: usize) -> Vec<u8> {
+    rgb.chunks(3).map(|pixel| pixel[channel]).collect()
+}
+
+/// Packs reduced-depth color frames into output signals, mirroring
+/// `pack_grayscale_frames_to_outputs` but quantizing each channel to `color_bits`
+/// total (RGB332/444/565) instead of collapsing to luma, so multiple color frames
+/// still fit into one 32-bit signal.
+///
+/// # Arguments
+///
+/// * `frames` - A slice of color image frames.
+/// * `signals` - The signals to map to each pixel.
+/// * `color_bits` - Total bits per pixel (8, 12, or 16).
+/// * `dither_mode` - 0 disables dithering, 1 selects Floyd–Steinberg error diffusion, 2
+///   selects ordered (Bayer matrix) dithering, applied per channel instead of truncating
+///   each pixel's channels independently.
+///
+/// # Returns
+///
+/// A vector of `CombinatorOutput`s representing the packed pixel values.
+pub fn pack_color_frames_to_outputs(
+    frames: &[image::DynamicImage],
+    signals: Vec<Arc<Signal>>,
+    color_bits: u32,
+    dither_mode: u32,
+) -> Result<Vec<CombinatorOutput>, JsValue> {
+    if frames.is_empty() {
+        return Err(JsValue::from_str("No frames provided for packing"));
+    }
+    let (width, height) = frames[0].dimensions();
+    let num_pixels = (width * height) as usize;
+    if num_pixels > signals.len() {
+        return Err(JsValue::from_str(&format!(
+            "Frame pixel count ({}) exceeds available signals ({}).",
+            num_pixels,
+            signals.len()
+        )));
+    }
+    let (r_bits, g_bits, b_bits) = color_channel_bits(color_bits);
+    let rgb_images: Vec<_> = frames.iter().map(|frame| frame.to_rgb8()).collect();
+    let dithered_channels: Vec<[Vec<u32>; 3]> = if dither_mode > 0 {
+        rgb_images
+            .iter()
+            .map(|img| {
+                let raw = img.as_raw();
+                [
+                    dither_levels(width, height, &extract_channel(raw, 0), 1 << r_bits, dither_mode).unwrap(),
+                    dither_levels(width, height, &extract_channel(raw, 1), 1 << g_bits, dither_mode).unwrap(),
+                    dither_levels(width, height, &extract_channel(raw, 2), 1 << b_bits, dither_mode).unwrap(),
+                ]
+            })
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut outputs = Vec::with_capacity(num_pixels);
+    for i in 0..num_pixels {
+        let mut packed_value: u32 = 0;
+        for (j, img) in rgb_images.iter().enumerate() {
+            let (r, g, b) = if let Some(channels) = dithered_channels.get(j) {
+                (channels[0][i], channels[1][i], channels[2][i])
+            } else {
+                let pixel = &img.as_raw()[i * 3..i * 3 + 3];
+                (
+                    (pixel[0] as u32) >> (8 - r_bits),
+                    (pixel[1] as u32) >> (8 - g_bits),
+                    (pixel[2] as u32) >> (8 - b_bits),
+                )
+            };
+            let reduced = (r << (g_bits + b_bits)) | (g << b_bits) | b;
+            packed_value |= reduced << (color_bits * j as u32);
+        }
+        let signal = Arc::clone(&signals[i]);
+        outputs.push(CombinatorOutput {
+            copy_count_from_input: false,
+            constant: Some(packed_value as i32),
+            signal,
+        });
+    }
+    Ok(outputs)
+}
+
+/// Generates the shifter/mask combinator chain that reconstructs a reduced-depth
+/// color frame from its packed signal, analogous to the grayscale `last_shifter`
+/// logic in `generate_frame_combinators`.
+///
+/// Assumes `shifter2` (the existing `signal-each` shift-by-`signal-F`-then-mask
+/// step already used for grayscale) has isolated this frame's `color_bits`-wide
+/// packed value onto `signal-each`. From there this chain splits that value into
+/// R/G/B in three independent branches (shift, mask, rescale to 0-255 with the same
+/// integer-multiply replication trick the grayscale path uses for 1-bit/4-bit, then
+/// shift into its final byte position), and relies on same-signal values merging
+/// additively when wired onto one network to recombine them into the final 24-bit
+/// color, rather than needing an explicit adder.
+///
+/// # Arguments
+///
+/// * `base_entity_number` - Entity number of the first combinator in the chain.
+/// * `base_x` / `base_y` - Placement for the chain.
+/// * `masked_value_entity` - Entity whose output (port 4) carries the masked,
+///   per-frame `signal-each` packed value (i.e. `shifter2` from `generate_frame_combinators`).
+/// * `color_bits` - Total bits per packed pixel (8, 12, or 16).
+///
+/// # Returns
+///
+/// The chain's entities, internal wires, and the three branch-final entity numbers
+/// (R, G, B) whose outputs should all be wired into the lamp's `rgb_signal` network
+/// so they sum together into the reconstructed color.
+pub fn generate_color_unpack_chain(
+    base_entity_number: u32,
+    base_x: f64,
+    base_y: f64,
+    masked_value_entity: u32,
+    color_bits: u32,
+) -> (Vec<Entity>, Vec<Wire>, [u32; 3]) {
+    let (r_bits, g_bits, b_bits) = color_channel_bits(color_bits);
+    let scale = |bits: u32| (255.0 / ((1u32 << bits) - 1) as f64).round() as i32;
+    let mut entities = Vec::with_capacity(10);
+    let mut wires = Vec::with_capacity(10);
+
+    let each_signal = || Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_EACH.to_string()), quality: None };
+    let mut push_step = |n: u32, x: f64, y: f64, op: &'static str, constant: i32| {
+        Entity::new(n, ARITHMETIC_COMBINATOR, Position { x, y })
+            .with_direction(DIRECTION_RIGHT)
+            .with_control_behavior(ControlBehavior::Arithmetic {
+                arithmetic_conditions: ArithmeticConditions {
+                    first_signal: each_signal(),
+                    second_signal: None,
+                    second_constant: Some(constant),
+                    operation: op,
+                    output_signal: each_signal(),
+                },
+            })
+    };
+
+    // One branch per channel: shift the channel into bit 0, mask off the other
+    // channels' bits, rescale up to 0-255, then shift into the channel's final
+    // byte position (skipped for blue, which is already the low byte).
+    let channels = [
+        (r_bits, (g_bits + b_bits) as i32, 16),
+        (g_bits, b_bits as i32, 8),
+        (b_bits, 0, 0),
+    ];
+    let mut finals = [0u32; 3];
+    let mut next_entity = base_entity_number;
+
+    for (i, &(bits, shift_in, shift_out)) in channels.iter().enumerate() {
+        let mut n = next_entity;
+        let shifted = n;
+        entities.push(push_step(n, base_x + i as f64, base_y, OPERATION_SHIFT_R, shift_in));
+        wires.push([masked_value_entity, 4, n, 2]);
+        n += 1;
+
+        let masked = n;
+        entities.push(push_step(n, base_x + i as f64, base_y + 1.0, OPERATION_AND, (1 << bits) - 1));
+        wires.push([shifted, 4, n, 2]);
+        n += 1;
+
+        entities.push(push_step(n, base_x + i as f64, base_y + 2.0, OPERATION_MUL, scale(bits)));
+        wires.push([masked, 4, n, 2]);
+        if shift_out == 0 {
+            finals[i] = n;
+        } else {
+            let rescaled = n;
+            n += 1;
+            entities.push(push_step(n, base_x + i as f64, base_y + 3.0, OPERATION_SHIFT_L, shift_out));
+            wires.push([rescaled, 4, n, 2]);
+            finals[i] = n;
+        }
+        next_entity = n + 1;
+    }
+
+    (entities, wires, finals)
+}
+
+/// Packs YCbCr 4:2:0 frames into output signals: one full-resolution Y sample per pixel
+/// (reusing the grayscale bit-packing convention) plus one Cb and one Cr sample per 2x2
+/// block, averaged over the block, so chroma costs a quarter of the constant-combinator
+/// filters luma does. Conversion uses the BT.601 coefficients.
+///
+/// # Arguments
+///
+/// * `frames` - A slice of color image frames.
+/// * `signals` - Signals for each pixel (`width * height` of them), immediately followed
+///   by one signal per 2x2 chroma block for Cb and then another for Cr.
+/// * `ycbcr_bits` - Number of bits to pack each Y/Cb/Cr sample to, one of 1, 4, or 8.
+///   These are the only depths whose bit-shift/mask extraction in `generate_frame_combinators`
+///   rescales the unpacked sample back to a full 0-255 range (via the same `*255`/`*17`
+///   multipliers grayscale mode uses); any other depth would decode with the wrong
+///   brightness, and anything above 8 would underflow the packing shift entirely.
+///
+/// # Returns
+///
+/// A vector of `CombinatorOutput`s: pixel Y values first, then block Cb values, then
+/// block Cr values.
+pub fn pack_ycbcr_frames_to_outputs(
+    frames: &[image::DynamicImage],
+    signals: Vec<Arc<Signal>>,
+    ycbcr_bits: u32,
+) -> Result<Vec<CombinatorOutput>, JsValue> {
+    if frames.is_empty() {
+        return Err(JsValue::from_str("No frames provided for packing"));
+    }
+    if ycbcr_bits != 1 && ycbcr_bits != 4 && ycbcr_bits != 8 {
+        return Err(JsValue::from_str(&format!(
+            "Unsupported ycbcr_bits value ({}); only 1, 4, or 8 decode correctly.",
+            ycbcr_bits
+        )));
+    }
+    let (width, height) = frames[0].dimensions();
+    let num_pixels = (width * height) as usize;
+    let block_width = ((width + 1) / 2) as usize;
+    let block_height = ((height + 1) / 2) as usize;
+    let num_blocks = block_width * block_height;
+    if num_pixels + num_blocks * 2 > signals.len() {
+        return Err(JsValue::from_str(&format!(
+            "Frame pixel+chroma-block count ({}) exceeds available signals ({}).",
+            num_pixels + num_blocks * 2,
+            signals.len()
+        )));
+    }
+
+    let y_shift = 8 - ycbcr_bits;
+    let rgb_images: Vec<_> = frames.iter().map(|frame| frame.to_rgb8()).collect();
+    let mut y_packed = vec![0u32; num_pixels];
+    let mut cb_packed = vec![0u32; num_blocks];
+    let mut cr_packed = vec![0u32; num_blocks];
+
+    for (j, img) in rgb_images.iter().enumerate() {
+        let mut cb_sum = vec![0u32; num_blocks];
+        let mut cr_sum = vec![0u32; num_blocks];
+        let mut block_count = vec![0u32; num_blocks];
+        for y in 0..height {
+            for x in 0..width {
+                let pixel = img.get_pixel(x, y);
+                let (r, g, b) = (pixel[0] as f64, pixel[1] as f64, pixel[2] as f64);
+                let luma = 0.299 * r + 0.587 * g + 0.114 * b;
+                let cb = 128.0 - 0.168736 * r - 0.331264 * g + 0.5 * b;
+                let cr = 128.0 + 0.5 * r - 0.418688 * g - 0.081312 * b;
+
+                let pixel_index = (y * width + x) as usize;
+                let quantized_y = (luma.round().clamp(0.0, 255.0) as u32) >> y_shift;
+                y_packed[pixel_index] |= quantized_y << (ycbcr_bits * j as u32);
+
+                let block_index = (y as usize / 2) * block_width + (x as usize / 2);
+                cb_sum[block_index] += cb.round().clamp(0.0, 255.0) as u32;
+                cr_sum[block_index] += cr.round().clamp(0.0, 255.0) as u32;
+                block_count[block_index] += 1;
+            }
+        }
+        for b in 0..num_blocks {
+            if block_count[b] == 0 {
+                continue;
+            }
+            let quantized_cb = (cb_sum[b] / block_count[b]) >> y_shift;
+            let quantized_cr = (cr_sum[b] / block_count[b]) >> y_shift;
+            cb_packed[b] |= quantized_cb << (ycbcr_bits * j as u32);
+            cr_packed[b] |= quantized_cr << (ycbcr_bits * j as u32);
+        }
+    }
+
+    let mut outputs = Vec::with_capacity(num_pixels + num_blocks * 2);
+    for (i, &value) in y_packed.iter().enumerate() {
+        outputs.push(CombinatorOutput { copy_count_from_input: false, constant: Some(value as i32), signal: Arc::clone(&signals[i]) });
+    }
+    for (b, &value) in cb_packed.iter().enumerate() {
+        outputs.push(CombinatorOutput { copy_count_from_input: false, constant: Some(value as i32), signal: Arc::clone(&signals[num_pixels + b]) });
+    }
+    for (b, &value) in cr_packed.iter().enumerate() {
+        outputs.push(CombinatorOutput { copy_count_from_input: false, constant: Some(value as i32), signal: Arc::clone(&signals[num_pixels + num_blocks + b]) });
+    }
+    Ok(outputs)
+}
+
+/// Finds the palette entry nearest a pixel by squared RGB distance, for indexed-palette
+/// color mode.
+fn nearest_palette_index(palette: &[(u8, u8, u8)], pixel: (u8, u8, u8)) -> u32 {
+    palette
+        .iter()
+        .enumerate()
+        .min_by_key(|(_, &(pr, pg, pb))| {
+            let dr = pixel.0 as i32 - pr as i32;
+            let dg = pixel.1 as i32 - pg as i32;
+            let db = pixel.2 as i32 - pb as i32;
+            dr * dr + dg * dg + db * db
+        })
+        .map(|(i, _)| i as u32)
+        .unwrap_or(0)
+}
+
+/// Error-diffusion (mode 1) or ordered (mode 2) dithers RGB pixels against an arbitrary
+/// palette, generalizing `floyd_steinberg_dither`/`bayer_dither`'s evenly-spaced-level
+/// quantization to `nearest_palette_index`'s nearest-neighbor lookup.
+///
+/// # Arguments
+///
+/// * `width` / `height` - Frame dimensions.
+/// * `rgb` - Row-major interleaved RGB samples (e.g. from `RgbImage::as_raw`).
+/// * `palette` - The shared palette to quantize against.
+/// * `dither_mode` - 1 for Floyd–Steinberg error diffusion, 2 for ordered (Bayer matrix).
+///
+/// # Returns
+///
+/// The nearest-palette index for each pixel, in row-major order.
+fn dither_palette_indices(width: u32, height: u32, rgb: &[u8], palette: &[(u8, u8, u8)], dither_mode: u32) -> Vec<u32> {
+    let width = width as usize;
+    let height = height as usize;
+    let mut indices = vec![0u32; width * height];
+
+    if dither_mode == 2 {
+        // There's no evenly-spaced "level" to nudge between for an arbitrary palette, so
+        // approximate one level's width from the palette's size, the same way `bayer_dither`
+        // derives its step from a power-of-two level count.
+        let step = 255.0 / (palette.len() as f32).cbrt().max(1.0);
+        for y in 0..height {
+            for x in 0..width {
+                let i = y * width + x;
+                let offset = (BAYER_MATRIX[y % 4][x % 4] as f32 / 16.0 - 0.5) * step;
+                let pixel = &rgb[i * 3..i * 3 + 3];
+                let nudged = (
+                    (pixel[0] as f32 + offset).round().clamp(0.0, 255.0) as u8,
+                    (pixel[1] as f32 + offset).round().clamp(0.0, 255.0) as u8,
+                    (pixel[2] as f32 + offset).round().clamp(0.0, 255.0) as u8,
+                );
+                indices[i] = nearest_palette_index(palette, nudged);
+            }
+        }
+        return indices;
+    }
+
+    let mut buffer: Vec<[f32; 3]> = rgb.chunks(3).map(|p| [p[0] as f32, p[1] as f32, p[2] as f32]).collect();
+
+    for y in 0..height {
+        for x in 0..width {
+            let i = y * width + x;
+            let old = buffer[i];
+            let clamped = (
+                old[0].round().clamp(0.0, 255.0) as u8,
+                old[1].round().clamp(0.0, 255.0) as u8,
+                old[2].round().clamp(0.0, 255.0) as u8,
+            );
+            let index = nearest_palette_index(palette, clamped);
+            indices[i] = index;
+            let chosen = palette[index as usize];
+            let err = [old[0] - chosen.0 as f32, old[1] - chosen.1 as f32, old[2] - chosen.2 as f32];
+
+            if x + 1 < width {
+                for c in 0..3 {
+                    buffer[i + 1][c] += err[c] * 7.0 / 16.0;
+                }
+            }
+            if y + 1 < height {
+                if x > 0 {
+                    for c in 0..3 {
+                        buffer[i + width - 1][c] += err[c] * 3.0 / 16.0;
+                    }
+                }
+                for c in 0..3 {
+                    buffer[i + width][c] += err[c] * 5.0 / 16.0;
+                }
+                if x + 1 < width {
+                    for c in 0..3 {
+                        buffer[i + width + 1][c] += err[c] * 1.0 / 16.0;
+                    }
+                }
+            }
+        }
+    }
+
+    indices
+}
+
+/// Packs indexed-palette frames into output signals, mirroring `pack_grayscale_frames_to_outputs`
+/// but storing each pixel's nearest-palette index instead of a luma or color value, so a 4-8 bit
+/// index (rather than a full 24-bit color) is what gets bit-packed per frame.
+///
+/// # Arguments
+///
+/// * `frames` - A slice of color image frames.
+/// * `signals` - The signals to map to each pixel.
+/// * `palette` - The shared palette built by `image_processing::build_palette`.
+/// * `palette_bits` - Bits per packed index (4/6/8 for a 16/64/256-color palette).
+/// * `dither_mode` - 0 disables dithering, 1 selects Floyd–Steinberg error diffusion, 2
+///   selects ordered (Bayer matrix) dithering against the shared palette.
+///
+/// # Returns
+///
+/// A vector of `CombinatorOutput`s representing the packed index values.
+pub fn pack_palette_frames_to_outputs(
+    frames: &[image::DynamicImage],
+    signals: Vec<Arc<Signal>>,
+    palette: &[(u8, u8, u8)],
+    palette_bits: u32,
+    dither_mode: u32,
+) -> Result<Vec<CombinatorOutput>, JsValue> {
+    if frames.is_empty() {
+        return Err(JsValue::from_str("No frames provided for packing"));
+    }
+    let (width, height) = frames[0].dimensions();
+    let num_pixels = (width * height) as usize;
+    if num_pixels > signals.len() {
+        return Err(JsValue::from_str(&format!(
+            "Frame pixel count ({}) exceeds available signals ({}).",
+            num_pixels,
+            signals.len()
+        )));
+    }
+    let rgb_images: Vec<_> = frames.iter().map(|frame| frame.to_rgb8()).collect();
+    let dithered: Vec<Vec<u32>> = if dither_mode > 0 {
+        rgb_images
+            .iter()
+            .map(|img| dither_palette_indices(width, height, img.as_raw(), palette, dither_mode))
+            .collect()
+    } else {
+        Vec::new()
+    };
+    let mut outputs = Vec::with_capacity(num_pixels);
+    for i in 0..num_pixels {
+        let mut packed_value: u32 = 0;
+        for (j, img) in rgb_images.iter().enumerate() {
+            let index = if let Some(frame_indices) = dithered.get(j) {
+                frame_indices[i]
+            } else {
+                let pixel = &img.as_raw()[i * 3..i * 3 + 3];
+                nearest_palette_index(palette, (pixel[0], pixel[1], pixel[2]))
+            };
+            packed_value |= index << (palette_bits * j as u32);
+        }
+        let signal = Arc::clone(&signals[i]);
+        outputs.push(CombinatorOutput {
+            copy_count_from_input: false,
+            constant: Some(packed_value as i32),
+            signal,
+        });
+    }
+    Ok(outputs)
+}
+
+/// Generates the LUT decoder for indexed-palette mode: a bank of decider combinators, one
+/// per palette entry, each comparing the frame's masked palette index against its own
+/// constant index and emitting the entry's packed `0xRRGGBB` color on a match.
+///
+/// Mirrors `generate_color_unpack_chain`'s assumption that `masked_value_entity` (the
+/// existing shift/mask step shared with grayscale) has already isolated this frame's
+/// palette index onto `signal-each`. Since at most one decider's condition is ever true
+/// for a given index, wiring every decider's output onto one network reconstructs the
+/// color without an explicit adder, the same trick the color chain uses for its branches.
+///
+/// # Arguments
+///
+/// * `base_entity_number` - Entity number of the first decider in the bank.
+/// * `base_x` / `base_y` - Placement for the bank.
+/// * `masked_value_entity` - Entity whose output (port 4) carries the masked, per-frame
+///   `signal-each` palette index (i.e. `shifter2` from `generate_frame_combinators`).
+/// * `palette` - Packed `0xRRGGBB` colors, indexed by palette index.
+///
+/// # Returns
+///
+/// The bank's entities, wires, and every decider's entity number, so the caller can wire
+/// them all onto the lamp's `rgb_signal` network.
+pub fn generate_palette_lut_decoder(
+    base_entity_number: u32,
+    base_x: f64,
+    base_y: f64,
+    masked_value_entity: u32,
+    palette: &[u32],
+) -> (Vec<Entity>, Vec<Wire>, Vec<u32>) {
+    let each_signal = || Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_EACH.to_string()), quality: None };
+    let mut entities = Vec::with_capacity(palette.len());
+    let mut wires = Vec::with_capacity(palette.len() * 2);
+    let mut finals = Vec::with_capacity(palette.len());
+
+    for (i, &color) in palette.iter().enumerate() {
+        let n = base_entity_number + i as u32;
+        let column = (i as f64 / 8.0).floor();
+        let row = (i % 8) as f64;
+        entities.push(
+            Entity::new(n, DECIDER_COMBINATOR, Position { x: base_x + column, y: base_y + row })
+                .with_direction(DIRECTION_RIGHT)
+                .with_control_behavior(ControlBehavior::Decider {
+                    decider_conditions: DeciderConditions {
+                        conditions: vec![Condition {
+                            first_signal: each_signal(),
+                            constant: i as i32,
+                            comparator: COMPARATOR_EQUALS,
+                            compare_type: None,
+                        }],
+                        outputs: vec![CombinatorOutput {
+                            copy_count_from_input: false,
+                            constant: Some(color as i32),
+                            signal: each_signal(),
+                        }],
+                    },
+                }),
+        );
+        wires.push([masked_value_entity, 4, n, 2]);
+        finals.push(n);
+    }
+
+    (entities, wires, finals)
+}
+
+/// Per-pixel fixed-point YCbCr→RGB decoder, built from arithmetic and decider combinators.
+///
+/// Reconstructs a pixel's packed `0xRRGGBB` lamp color from its own masked Y sample and
+/// its block's shared Cb/Cr samples (all three already isolated on `signal-each` by the
+/// usual shift/mask pipeline in `generate_frame_combinators`). Every coefficient is scaled
+/// by 256 so the Y term (coefficient exactly 256) and the chroma terms share one `>> 8`
+/// descale step, mirroring BT.601:
+///   R = Y + 1.402*(Cr-128), G = Y - 0.344136*(Cb-128) - 0.714136*(Cr-128), B = Y + 1.772*(Cb-128)
+/// Each channel is shifted into its byte position and then clamped to 0..255 (at that byte
+/// position) by three mutually-exclusive deciders (in-range, below, above), then relies on same-signal
+/// wire summation (as in `generate_color_unpack_chain`) to recombine R/G/B into one color.
+///
+/// # Arguments
+///
+/// * `base_entity_number` - Entity number of the first combinator in the chain.
+/// * `base_x` / `base_y` - Placement for the chain.
+/// * `masked_value_entity` - Entity whose output (port 4) carries this frame's Y/Cb/Cr
+///   samples already rescaled to a 0..255 range, since the BT.601 coefficients below
+///   assume that range. This is `shifter2` from `generate_frame_combinators` for 8-bit
+///   samples (already 0..255), or its rescale-multiply follow-up for 1/4-bit samples
+///   (which `shifter2` alone leaves in 0..(2^bits-1)).
+/// * `pixel_signal` - The pixel's own dedicated signal, used for both the Y input and the
+///   final reconstructed color output.
+/// * `cb_signal` / `cr_signal` - The pixel's block's shared chroma signals.
+///
+/// # Returns
+///
+/// The chain's entities, internal wires, and every entity whose output should be wired
+/// into the lamp's `rgb_signal` network.
+fn generate_ycbcr_pixel_decoder(
+    base_entity_number: u32,
+    base_x: f64,
+    base_y: f64,
+    masked_value_entity: u32,
+    pixel_signal: &Arc<Signal>,
+    cb_signal: &Arc<Signal>,
+    cr_signal: &Arc<Signal>,
+) -> (Vec<Entity>, Vec<Wire>, Vec<u32>) {
+    let scratch = || Signal { type_: Arc::new(SIGNAL_TYPE_VIRTUAL.to_string()), name: Arc::new(SIGNAL_EACH.to_string()), quality: None };
+    let y_sig = || Signal { type_: Arc::new(pixel_signal.type_.to_string()), name: Arc::new(pixel_signal.name.to_string()), quality: None };
+    let cb_sig = || Signal { type_: Arc::new(cb_signal.type_.to_string()), name: Arc::new(cb_signal.name.to_string()), quality: None };
+    let cr_sig = || Signal { type_: Arc::new(cr_signal.type_.to_string()), name: Arc::new(cr_signal.name.to_string()), quality: None };
+
+    let mut entities = Vec::with_capacity(28);
+    let mut wires = Vec::with_capacity(32);
+    let mut n = base_entity_number;
+
+    let push_arith = |n: u32, x: f64, y: f64, first: Signal, op: &'static str, constant: i32, output: Signal| {
+        Entity::new(n, ARITHMETIC_COMBINATOR, Position { x, y })
+            .with_direction(DIRECTION_RIGHT)
+            .with_control_behavior(ControlBehavior::Arithmetic {
+                arithmetic_conditions: ArithmeticConditions {
+                    first_signal: first,
+                    second_signal: None,
+                    second_constant: Some(constant),
+                    operation: op,
+                    output_signal: output,
+                },
+            })
+    };
+
+    // Center Cb/Cr around 0 once; every band that needs them reads from these two entities.
+    let cb_centered = n;
+    entities.push(push_arith(n, base_x - 1.0, base_y, cb_sig(), OPERATION_SUB, 128, scratch()));
+    wires.push([masked_value_entity, 4, n, 2]);
+    n += 1;
+
+    let cr_centered = n;
+    entities.push(push_arith(n, base_x - 1.0, base_y + 1.0, cr_sig(), OPERATION_SUB, 128, scratch()));
+    wires.push([masked_value_entity, 4, n, 2]);
+    n += 1;
+
+    enum Term {
+        Y,
+        Cb(i32),
+        Cr(i32),
+    }
+    // (byte shift, terms) per band. Y's coefficient is exactly 256 so every band shares
+    // the same `>> 8` descale step regardless of which chroma terms it also sums in.
+    let bands: [(f64, i32, &[Term]); 3] = [
+        (0.0, 16, &[Term::Y, Term::Cr(359)]),
+        (1.0, 8, &[Term::Y, Term::Cb(-88), Term::Cr(-183)]),
+        (2.0, 0, &[Term::Y, Term::Cb(454)]),
+    ];
+
+    let mut finals = Vec::with_capacity(9);
+    for &(band_x, byte_shift, terms) in bands.iter() {
+        let x = base_x + band_x;
+        let mut term_entities = Vec::with_capacity(terms.len());
+        for (row, term) in terms.iter().enumerate() {
+            let (source, first, mult) = match *term {
+                Term::Y => (masked_value_entity, y_sig(), 256),
+                Term::Cb(m) => (cb_centered, scratch(), m),
+                Term::Cr(m) => (cr_centered, scratch(), m),
+            };
+            entities.push(push_arith(n, x, base_y + row as f64, first, OPERATION_MUL, mult, scratch()));
+            wires.push([source, 4, n, 2]);
+            term_entities.push(n);
+            n += 1;
+        }
+
+        let descale = n;
+        entities.push(push_arith(n, x, base_y + terms.len() as f64, scratch(), OPERATION_SHIFT_R, 8, scratch()));
+        for &t in &term_entities {
+            wires.push([t, 4, n, 2]);
+        }
+        n += 1;
+
+        let positioned = if byte_shift == 0 {
+            descale
+        } else {
+            let placed = n;
+            entities.push(push_arith(n, x, base_y + terms.len() as f64 + 1.0, scratch(), OPERATION_SHIFT_L, byte_shift, scratch()));
+            wires.push([descale, 4, n, 2]);
+            n += 1;
+            placed
+        };
+
+        // Clamp to 0..255 (scaled to the band's byte position) with three mutually-exclusive
+        // deciders (in range, below, above), all writing the pixel's own signal; exactly one
+        // contributes on any given tick.
+        let clamp_y = base_y + terms.len() as f64 + 2.0;
+        let in_range = n;
+        entities.push(
+            Entity::new(n, DECIDER_COMBINATOR, Position { x, y: clamp_y })
+                .with_direction(DIRECTION_RIGHT)
+                .with_control_behavior(ControlBehavior::Decider {
+                    decider_conditions: DeciderConditions {
+                        conditions: vec![
+                            Condition { first_signal: scratch(), constant: 0, comparator: COMPARATOR_GREATER_EQUAL, compare_type: None },
+                            Condition { first_signal: scratch(), constant: 256 << byte_shift, comparator: COMPARATOR_LESS, compare_type: Some(COMPARE_AND) },
+                        ],
+                        outputs: vec![CombinatorOutput { copy_count_from_input: true, constant: None, signal: y_sig() }],
+                    },
+                }),
+        );
+        wires.push([positioned, 4, n, 2]);
+        wires.push([positioned, 4, n, 3]);
+        n += 1;
+
+        let below_range = n;
+        entities.push(
+            Entity::new(n, DECIDER_COMBINATOR, Position { x, y: clamp_y + 1.0 })
+                .with_direction(DIRECTION_RIGHT)
+                .with_control_behavior(ControlBehavior::Decider {
+                    decider_conditions: DeciderConditions {
+                        conditions: vec![Condition { first_signal: scratch(), constant: 0, comparator: COMPARATOR_LESS, compare_type: None }],
+                        outputs: vec![CombinatorOutput { copy_count_from_input: false, constant: Some(0), signal: y_sig() }],
+                    },
+                }),
+        );
+        wires.push([positioned, 4, n, 2]);
+        n += 1;
+
+        let above_range = n;
+        entities.push(
+            Entity::new(n, DECIDER_COMBINATOR, Position { x, y: clamp_y + 2.0 })
+                .with_direction(DIRECTION_RIGHT)
+                .with_control_behavior(ControlBehavior::Decider {
+                    decider_conditions: DeciderConditions {
+                        conditions: vec![Condition { first_signal: scratch(), constant: 256 << byte_shift, comparator: COMPARATOR_GREATER_EQUAL, compare_type: None }],
+                        outputs: vec![CombinatorOutput { copy_count_from_input: false, constant: Some(255 << byte_shift), signal: y_sig() }],
+                    },
+                }),
+        );
+        wires.push([positioned, 4, n, 2]);
+        n += 1;
+
+        finals.push(in_range);
+        finals.push(below_range);
+        finals.push(above_range);
+    }
+
+    (entities, wires, finals)
+}
@@ -22,6 +22,28 @@ pub const BLUEPRINT_VERSION: u64 = 562949955518464;
 /// Threshold used for binary grayscale conversion.
 pub const GRAYSCALE_THRESHOLD: u8 = 128;
 
+/// Maximum number of residual wire entries a delta-encoded group may accumulate
+/// before a fresh keyframe is forced, so drift-prone accumulators never grow unbounded.
+pub const DELTA_KEYFRAME_WIRE_BUDGET: usize = 4096;
+
+/// Per-group delta-accumulator reset marker: a keyframe's single emission tick carries this
+/// signal at 1 so the accumulator's feedback gate can tell "this tick replaces the running
+/// total" apart from "this tick adds a residual to it".
+pub const SIGNAL_K: &'static str = "signal-K";
+
+/// Companion to `SIGNAL_K`: 1 on every tick except a reset pulse, multiplied into the
+/// accumulator's fed-back total so a keyframe's fresh value isn't added on top of stale state.
+pub const SIGNAL_H: &'static str = "signal-H";
+
+/// Maximum mean per-channel difference (0-255 scale) for two consecutive decoded frames
+/// to be treated as visually identical and merged into one during frame deduplication.
+pub const DUPLICATE_FRAME_MEAN_DIFF_THRESHOLD: f64 = 1.0;
+
+/// Lowest and highest content framerate the GCD-based detector will trust before
+/// falling back to averaging frame delays instead.
+pub const MIN_SANE_FPS: u32 = 1;
+pub const MAX_SANE_FPS: u32 = 60;
+
 /// Quality constants.
 pub const QUALITY_NORMAL: &str = "normal";
 pub const QUALITY_UNCOMMON: &'static str = "uncommon";
@@ -50,6 +72,7 @@ pub const SIGNAL_EACH: &'static str = "signal-each";
 
 /// Comparators
 pub const COMPARATOR_EQUAL: &'static str = "equal";
+pub const COMPARATOR_EQUALS: &'static str = "=";
 pub const COMPARATOR_GREATER_EQUAL: &'static str = ">=";
 pub const COMPARATOR_LESS: &'static str = "<";
 
@@ -59,4 +82,5 @@ pub const OPERATION_MUL: &'static str = "*";
 pub const OPERATION_DIV: &'static str = "/";
 pub const OPERATION_SUB: &'static str = "-";
 pub const OPERATION_SHIFT_R: &'static str = ">>";
+pub const OPERATION_SHIFT_L: &'static str = "<<";
 pub const OPERATION_AND: &'static str = "AND";
\ No newline at end of file
@@ -0,0 +1,275 @@
+use image::{DynamicImage, GenericImageView, RgbaImage};
+
+/// WGSL compute shader that downsamples an RGBA frame to the target dimensions with a
+/// box filter (averaging the source texels each destination texel covers, the same
+/// neighborhood `FilterType::Triangle` blends on the CPU path) and, when `grayscale_bits`
+/// is non-zero, collapses the result to a quantized luma value in the same pass so the
+/// reduction doesn't need a second dispatch.
+const DOWNSAMPLE_QUANTIZE_SHADER: &str = r#"
+struct Params {
+    src_size: vec2<u32>,
+    dst_size: vec2<u32>,
+    grayscale_levels: u32,
+    _pad: u32,
+};
+
+@group(0) @binding(0) var src_texture: texture_2d<f32>;
+@group(0) @binding(1) var dst_texture: texture_storage_2d<rgba8unorm, write>;
+@group(0) @binding(2) var<uniform> params: Params;
+
+@compute @workgroup_size(8, 8, 1)
+fn main(@builtin(global_invocation_id) gid: vec3<u32>) {
+    if (gid.x >= params.dst_size.x || gid.y >= params.dst_size.y) {
+        return;
+    }
+
+    let scale = vec2<f32>(params.src_size) / vec2<f32>(params.dst_size);
+    let src_origin = vec2<f32>(gid.xy) * scale;
+    let src_extent = max(vec2<u32>(ceil(scale)), vec2<u32>(1u, 1u));
+
+    var sum = vec4<f32>(0.0, 0.0, 0.0, 0.0);
+    var count = 0.0;
+    for (var dy = 0u; dy < src_extent.y; dy = dy + 1u) {
+        for (var dx = 0u; dx < src_extent.x; dx = dx + 1u) {
+            let coord = vec2<u32>(src_origin) + vec2<u32>(dx, dy);
+            if (coord.x < params.src_size.x && coord.y < params.src_size.y) {
+                sum = sum + textureLoad(src_texture, coord, 0);
+                count = count + 1.0;
+            }
+        }
+    }
+    var color = sum / max(count, 1.0);
+
+    if (params.grayscale_levels > 0u) {
+        let luma = dot(color.rgb, vec3<f32>(0.299, 0.587, 0.114));
+        let levels = f32(params.grayscale_levels);
+        let quantized = round(luma * (levels - 1.0)) / (levels - 1.0);
+        color = vec4<f32>(quantized, quantized, quantized, color.a);
+    }
+
+    textureStore(dst_texture, vec2<i32>(gid.xy), color);
+}
+"#;
+
+/// Attempts to downscale and (optionally) grayscale-quantize a batch of frames on the
+/// GPU via a `wgpu` compute pipeline, in one pass per frame, so `process_image` can skip
+/// its CPU `resize`/`to_luma8` step entirely when a backend is available.
+///
+/// The device, queue, shader and pipeline are created once and shared by every frame in
+/// the batch. Each frame still gets its own uniform buffer, submit, staging buffer and
+/// blocking readback, so the host-device round trip is paid once per frame; uploads and
+/// readbacks are not combined across frames. Runs over
+/// WebGPU in the browser and natively via Vulkan/Metal/DX12 when used as a library.
+///
+/// # Arguments
+///
+/// * `frames` - Decoded RGBA frames, pre-resize.
+/// * `max_size` - Maximum width/height for downscaling (same semantics as the CPU path).
+/// * `grayscale_bits` - Number of bits for grayscale conversion (0 means full color).
+///
+/// # Returns
+///
+/// `Some(frames)` as RGBA images at the same target size `process_image`'s CPU path
+/// computes, box-filtered rather than `Triangle`-resized and, when `grayscale_bits` is
+/// non-zero, gray with luma rounded to `1 << grayscale_bits` levels, or `None` if no
+/// `wgpu` adapter is available (e.g. no GPU, or WebGPU unsupported in the host browser),
+/// so the caller can fall back to the CPU path transparently.
+pub fn try_gpu_downscale_and_quantize(
+    frames: &[DynamicImage],
+    max_size: u32,
+    grayscale_bits: u32,
+) -> Option<Vec<DynamicImage>> {
+    pollster::block_on(try_gpu_downscale_and_quantize_async(frames, max_size, grayscale_bits))
+}
+
+async fn try_gpu_downscale_and_quantize_async(
+    frames: &[DynamicImage],
+    max_size: u32,
+    grayscale_bits: u32,
+) -> Option<Vec<DynamicImage>> {
+    if frames.is_empty() {
+        return Some(Vec::new());
+    }
+
+    let instance = wgpu::Instance::default();
+    let adapter = instance
+        .request_adapter(&wgpu::RequestAdapterOptions::default())
+        .await
+        .ok()?;
+    let (device, queue) = adapter
+        .request_device(&wgpu::DeviceDescriptor::default())
+        .await
+        .ok()?;
+
+    let shader = device.create_shader_module(wgpu::ShaderModuleDescriptor {
+        label: Some("downsample_quantize"),
+        source: wgpu::ShaderSource::Wgsl(DOWNSAMPLE_QUANTIZE_SHADER.into()),
+    });
+    let pipeline = device.create_compute_pipeline(&wgpu::ComputePipelineDescriptor {
+        label: Some("downsample_quantize_pipeline"),
+        layout: None,
+        module: &shader,
+        entry_point: "main",
+        compilation_options: Default::default(),
+        cache: None,
+    });
+
+    let mut reduced = Vec::with_capacity(frames.len());
+    for frame in frames {
+        let (src_width, src_height) = frame.dimensions();
+        let scale_factor = (max_size as f64 / src_width as f64)
+            .min(max_size as f64 / src_height as f64)
+            .min(1.0);
+        let dst_width = ((src_width as f64 * scale_factor).round() as u32).max(1);
+        let dst_height = ((src_height as f64 * scale_factor).round() as u32).max(1);
+
+        let rgba = frame.to_rgba8();
+        let buffer = gpu_reduce_one_frame(
+            &device,
+            &queue,
+            &pipeline,
+            &rgba,
+            src_width,
+            src_height,
+            dst_width,
+            dst_height,
+            grayscale_bits,
+        )
+        .await?;
+        reduced.push(DynamicImage::ImageRgba8(buffer));
+    }
+
+    Some(reduced)
+}
+
+/// Runs one frame through the compute pipeline and reads the result back into a plain
+/// `RgbaImage`. Split out of the batch loop above purely so the per-frame texture/buffer
+/// setup doesn't all live in one giant function.
+#[allow(clippy::too_many_arguments)]
+async fn gpu_reduce_one_frame(
+    device: &wgpu::Device,
+    queue: &wgpu::Queue,
+    pipeline: &wgpu::ComputePipeline,
+    rgba: &RgbaImage,
+    src_width: u32,
+    src_height: u32,
+    dst_width: u32,
+    dst_height: u32,
+    grayscale_bits: u32,
+) -> Option<RgbaImage> {
+    let src_texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("src_frame"),
+        size: wgpu::Extent3d { width: src_width, height: src_height, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::TEXTURE_BINDING | wgpu::TextureUsages::COPY_DST,
+        view_formats: &[],
+    });
+    queue.write_texture(
+        src_texture.as_image_copy(),
+        rgba,
+        wgpu::ImageDataLayout {
+            offset: 0,
+            bytes_per_row: Some(4 * src_width),
+            rows_per_image: Some(src_height),
+        },
+        wgpu::Extent3d { width: src_width, height: src_height, depth_or_array_layers: 1 },
+    );
+
+    let dst_texture = device.create_texture(&wgpu::TextureDescriptor {
+        label: Some("dst_frame"),
+        size: wgpu::Extent3d { width: dst_width, height: dst_height, depth_or_array_layers: 1 },
+        mip_level_count: 1,
+        sample_count: 1,
+        dimension: wgpu::TextureDimension::D2,
+        format: wgpu::TextureFormat::Rgba8Unorm,
+        usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::COPY_SRC,
+        view_formats: &[],
+    });
+
+    let grayscale_levels: u32 = if grayscale_bits > 0 { 1 << grayscale_bits } else { 0 };
+    let params = [src_width, src_height, dst_width, dst_height, grayscale_levels, 0u32];
+    let params_buffer = wgpu::util::DeviceExt::create_buffer_init(
+        device,
+        &wgpu::util::BufferInitDescriptor {
+            label: Some("params"),
+            contents: bytemuck::cast_slice(&params),
+            usage: wgpu::BufferUsages::UNIFORM,
+        },
+    );
+
+    let bind_group_layout = pipeline.get_bind_group_layout(0);
+    let bind_group = device.create_bind_group(&wgpu::BindGroupDescriptor {
+        label: Some("downsample_quantize_bind_group"),
+        layout: &bind_group_layout,
+        entries: &[
+            wgpu::BindGroupEntry {
+                binding: 0,
+                resource: wgpu::BindingResource::TextureView(
+                    &src_texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                ),
+            },
+            wgpu::BindGroupEntry {
+                binding: 1,
+                resource: wgpu::BindingResource::TextureView(
+                    &dst_texture.create_view(&wgpu::TextureViewDescriptor::default()),
+                ),
+            },
+            wgpu::BindGroupEntry { binding: 2, resource: params_buffer.as_entire_binding() },
+        ],
+    });
+
+    let mut encoder = device.create_command_encoder(&wgpu::CommandEncoderDescriptor::default());
+    {
+        let mut pass = encoder.begin_compute_pass(&wgpu::ComputePassDescriptor::default());
+        pass.set_pipeline(pipeline);
+        pass.set_bind_group(0, &bind_group, &[]);
+        pass.dispatch_workgroups(dst_width.div_ceil(8), dst_height.div_ceil(8), 1);
+    }
+
+    // One staging buffer and one blocking readback per frame; only the device, queue and
+    // pipeline are shared across the batch.
+    let bytes_per_row = (4 * dst_width).div_ceil(256) * 256;
+    let staging_buffer = device.create_buffer(&wgpu::BufferDescriptor {
+        label: Some("readback"),
+        size: (bytes_per_row * dst_height) as u64,
+        usage: wgpu::BufferUsages::COPY_DST | wgpu::BufferUsages::MAP_READ,
+        mapped_at_creation: false,
+    });
+    encoder.copy_texture_to_buffer(
+        dst_texture.as_image_copy(),
+        wgpu::ImageCopyBuffer {
+            buffer: &staging_buffer,
+            layout: wgpu::ImageDataLayout {
+                offset: 0,
+                bytes_per_row: Some(bytes_per_row),
+                rows_per_image: Some(dst_height),
+            },
+        },
+        wgpu::Extent3d { width: dst_width, height: dst_height, depth_or_array_layers: 1 },
+    );
+    queue.submit(Some(encoder.finish()));
+
+    let slice = staging_buffer.slice(..);
+    let (tx, rx) = futures_intrusive::channel::shared::oneshot_channel();
+    slice.map_async(wgpu::MapMode::Read, move |result| {
+        let _ = tx.send(result);
+    });
+    device.poll(wgpu::Maintain::Wait);
+    rx.receive().await?.ok()?;
+
+    let data = slice.get_mapped_range();
+    let mut out = RgbaImage::new(dst_width, dst_height);
+    for y in 0..dst_height as usize {
+        let row_start = y * bytes_per_row as usize;
+        let row = &data[row_start..row_start + dst_width as usize * 4];
+        out.as_flat_samples_mut().samples[y * dst_width as usize * 4..(y + 1) * dst_width as usize * 4]
+            .copy_from_slice(row);
+    }
+    drop(data);
+    staging_buffer.unmap();
+
+    Some(out)
+}
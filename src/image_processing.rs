@@ -4,26 +4,30 @@ use image::imageops::FilterType;
 use rayon::prelude::*;
 use std::io::Cursor;
 use wasm_bindgen::prelude::*;
-use crate::constants::{DEFAULT_FRAME_DELAY_MS, MS_PER_SECOND};
+use crate::constants::{DEFAULT_FRAME_DELAY_MS, DUPLICATE_FRAME_MEAN_DIFF_THRESHOLD, MAX_SANE_FPS, MIN_SANE_FPS, MS_PER_SECOND};
 
 /// Decodes the provided image data into a vector of frames based on the image type.
 ///
-/// Supported types: "gif" and "webp".
+/// Supported types: "gif", "webp", "mp4", and "webm".
 ///
 /// # Arguments
 ///
 /// * `image_data` - A byte slice containing the image data.
-/// * `image_type` - The type of the image ("gif" or "webp").
+/// * `image_type` - The type of the image ("gif", "webp", "mp4", or "webm").
 ///
 /// # Returns
 ///
 /// A vector of `Frame` objects or a JavaScript error.
 pub fn get_frames(image_data: &[u8], image_type: &str) -> Result<Vec<Frame>, JsValue> {
+    if image_type == "mp4" || image_type == "webm" {
+        return get_video_frames(image_data);
+    }
+
     let cursor = Cursor::new(image_data);
     let decoder_result = match image_type {
         "gif" => image::codecs::gif::GifDecoder::new(cursor).map(|d| d.into_frames()),
         "webp" => image::codecs::webp::WebPDecoder::new(cursor).map(|d| d.into_frames()),
-        _ => return Err(JsValue::from_str("Unsupported image type. Only 'gif' and 'webp' are allowed.")),
+        _ => return Err(JsValue::from_str("Unsupported image type. Only 'gif', 'webp', 'mp4', and 'webm' are allowed.")),
     };
 
     let frames = decoder_result
@@ -33,6 +37,319 @@ pub fn get_frames(image_data: &[u8], image_type: &str) -> Result<Vec<Frame>, JsV
         .map_err(|e| JsValue::from_str(&format!("Frame collection error: {}", e)))
 }
 
+/// Demuxes and decodes an MP4/H.264 or WebM/VP8-VP9 container into the same `Vec<Frame>`
+/// shape the GIF/WebP decoders produce, so the rest of `get_frames`'s callers (and the
+/// duration-based sampling in `process_image`) don't need to know a video was involved.
+///
+/// Unlike GIF/WebP, containers don't attach a per-frame display delay, so each decoded
+/// frame's delay is derived from the delta between its presentation timestamp (PTS) and
+/// the next frame's, converted through the stream's time base. The container format
+/// itself (MP4 vs. WebM) doesn't need to be told apart here: both demux and decode through
+/// the same codec-agnostic path, with ffmpeg probing the container and picking the right
+/// codec (H.264, VP8, or VP9) from the stream itself.
+///
+/// # Arguments
+///
+/// * `image_data` - Raw container bytes (MP4 or WebM).
+///
+/// # Returns
+///
+/// A vector of `Frame` objects or a JavaScript error.
+///
+/// This pulls in `ffmpeg_next`, a binding to native libav*, which doesn't build for
+/// `wasm32` — the crate's actual `#[wasm_bindgen]` target. It's only compiled for non-wasm
+/// targets (e.g. native test/CLI builds); the `wasm32` build instead gets the stub below
+/// that reports mp4/webm as unsupported rather than failing to link.
+#[cfg(not(target_arch = "wasm32"))]
+fn get_video_frames(image_data: &[u8]) -> Result<Vec<Frame>, JsValue> {
+    use ffmpeg_next as ffmpeg;
+
+    ffmpeg::init().map_err(|e| JsValue::from_str(&format!("Video decoder init error: {}", e)))?;
+
+    let mut input = ffmpeg::format::io::input_from_slice(image_data)
+        .map_err(|e| JsValue::from_str(&format!("Video demux error: {}", e)))?;
+
+    let stream = input
+        .streams()
+        .best(ffmpeg::media::Type::Video)
+        .ok_or_else(|| JsValue::from_str("No video stream found in container"))?;
+    let stream_index = stream.index();
+    let time_base: f64 = stream.time_base().into();
+
+    let mut decoder = ffmpeg::codec::context::Context::from_parameters(stream.parameters())
+        .map_err(|e| JsValue::from_str(&format!("Video codec error: {}", e)))?
+        .decoder()
+        .video()
+        .map_err(|e| JsValue::from_str(&format!("Video decoder error: {}", e)))?;
+
+    let mut scaler = ffmpeg::software::scaling::Context::get(
+        decoder.format(),
+        decoder.width(),
+        decoder.height(),
+        ffmpeg::format::Pixel::RGBA,
+        decoder.width(),
+        decoder.height(),
+        ffmpeg::software::scaling::Flags::BILINEAR,
+    )
+    .map_err(|e| JsValue::from_str(&format!("Video scaler error: {}", e)))?;
+
+    let mut buffers = Vec::new();
+    let mut pts_ms = Vec::new();
+    let mut decoded = ffmpeg::util::frame::Video::empty();
+    let mut rgba = ffmpeg::util::frame::Video::empty();
+
+    let mut decode_queued = |decoder: &mut ffmpeg::decoder::Video| -> Result<(), JsValue> {
+        while decoder.receive_frame(&mut decoded).is_ok() {
+            scaler
+                .run(&decoded, &mut rgba)
+                .map_err(|e| JsValue::from_str(&format!("Video scale error: {}", e)))?;
+
+            let width = rgba.width();
+            let height = rgba.height();
+            let stride = rgba.stride(0);
+            let mut buffer = image::RgbaImage::new(width, height);
+            for y in 0..height as usize {
+                let row_start = y * stride;
+                let row = &rgba.data(0)[row_start..row_start + width as usize * 4];
+                buffer.as_flat_samples_mut().samples[y * width as usize * 4..(y + 1) * width as usize * 4]
+                    .copy_from_slice(row);
+            }
+
+            pts_ms.push(decoded.pts().unwrap_or(0) as f64 * time_base * MS_PER_SECOND);
+            buffers.push(buffer);
+        }
+        Ok(())
+    };
+
+    for (stream, packet) in input.packets() {
+        if stream.index() != stream_index {
+            continue;
+        }
+        decoder
+            .send_packet(&packet)
+            .map_err(|e| JsValue::from_str(&format!("Video decode error: {}", e)))?;
+        decode_queued(&mut decoder)?;
+    }
+    decoder
+        .send_eof()
+        .map_err(|e| JsValue::from_str(&format!("Video decode error: {}", e)))?;
+    decode_queued(&mut decoder)?;
+
+    if buffers.is_empty() {
+        return Err(JsValue::from_str("No frames decoded from video"));
+    }
+
+    // Each frame's delay comes from the PTS delta to the next frame; the last frame
+    // reuses the prior delta (or DEFAULT_FRAME_DELAY_MS if there's only one frame).
+    let frames = buffers
+        .into_iter()
+        .enumerate()
+        .map(|(i, buffer)| {
+            let delay_ms = if i + 1 < pts_ms.len() {
+                (pts_ms[i + 1] - pts_ms[i]).round().max(0.0) as u32
+            } else if i > 0 {
+                (pts_ms[i] - pts_ms[i - 1]).round().max(0.0) as u32
+            } else {
+                DEFAULT_FRAME_DELAY_MS
+            };
+            Frame::from_parts(buffer, 0, 0, image::Delay::from_numer_denom_ms(delay_ms, 1))
+        })
+        .collect();
+
+    Ok(frames)
+}
+
+/// `wasm32` stand-in for the native `get_video_frames` above: `ffmpeg_next` doesn't build
+/// for `wasm32`, so the `#[wasm_bindgen]` build reports mp4/webm as unsupported instead of
+/// failing to link.
+#[cfg(target_arch = "wasm32")]
+fn get_video_frames(_image_data: &[u8]) -> Result<Vec<Frame>, JsValue> {
+    Err(JsValue::from_str("mp4/webm input isn't supported in the WebAssembly build"))
+}
+
+/// Cheaply probes an animation's dimensions and timing without resizing or quantizing
+/// any frames, so a rate controller can size its parameters before doing real work.
+///
+/// # Arguments
+///
+/// * `image_data` - Raw image data.
+/// * `image_type` - Image type (e.g., "gif" or "webp").
+///
+/// # Returns
+///
+/// A tuple of `(width, height, total_duration_ms, original_fps)`.
+pub fn probe_animation(image_data: &[u8], image_type: &str) -> Result<(u32, u32, u32, u32), JsValue> {
+    let frame_vec = get_frames(image_data, image_type)?;
+    if frame_vec.is_empty() {
+        return Err(JsValue::from_str("No frames found!"));
+    }
+    let (width, height) = frame_vec[0].buffer().dimensions();
+
+    let (merged, original_fps) = dedupe_and_detect_fps(&frame_vec);
+    let total_ms: u32 = merged.iter().map(|&(_, delay)| delay).sum();
+
+    Ok((width, height, total_ms, original_fps))
+}
+
+/// Computes the greatest common divisor of two non-negative integers.
+fn gcd(a: u32, b: u32) -> u32 {
+    if b == 0 {
+        a
+    } else {
+        gcd(b, a % b)
+    }
+}
+
+/// Mean absolute per-channel difference between two frames' raw RGBA buffers, used to
+/// tell whether two consecutive frames are visually identical.
+fn mean_channel_diff(a: &Frame, b: &Frame) -> f64 {
+    let a_buf = a.buffer();
+    let b_buf = b.buffer();
+    if a_buf.dimensions() != b_buf.dimensions() {
+        return f64::MAX;
+    }
+    let total: u64 = a_buf
+        .as_raw()
+        .iter()
+        .zip(b_buf.as_raw().iter())
+        .map(|(&x, &y)| (x as i64 - y as i64).unsigned_abs())
+        .sum();
+    total as f64 / a_buf.as_raw().len() as f64
+}
+
+/// Detects the content's true base frame rate and collapses runs of visually-identical
+/// consecutive frames into one.
+///
+/// The base tick is recovered from the GCD of all non-zero frame delays, rather than
+/// naively averaging them, since a variable-delay GIF otherwise reports a skewed FPS.
+/// When every delay is zero, or the GCD implies an FPS outside `MIN_SANE_FPS..=MAX_SANE_FPS`
+/// (e.g. a single stray 1ms delay dragging the GCD down), this falls back to the average
+/// frame duration instead. Runs of frames that are pixel-identical or whose mean
+/// per-channel difference falls under `DUPLICATE_FRAME_MEAN_DIFF_THRESHOLD` are merged
+/// into a single representative frame whose delay is the sum of the run's delays, so the
+/// sampler in `process_image` never wastes combinators on a repeated frame.
+///
+/// # Arguments
+///
+/// * `frame_vec` - Decoded frames, in display order.
+///
+/// # Returns
+///
+/// A tuple of the deduplicated `(index into frame_vec, merged delay in ms)` list and the
+/// detected original FPS.
+fn dedupe_and_detect_fps(frame_vec: &[Frame]) -> (Vec<(usize, u32)>, u32) {
+    let raw_ms: Vec<u32> = frame_vec.iter().map(|f| f.delay().numer_denom_ms().0).collect();
+    let delays: Vec<u32> = raw_ms.iter().map(|&ms| if ms == 0 { DEFAULT_FRAME_DELAY_MS } else { ms }).collect();
+
+    let average_fps = (MS_PER_SECOND / (delays.iter().sum::<u32>() as f64 / delays.len() as f64))
+        .floor()
+        .max(1.0) as u32;
+    let base_tick = raw_ms.iter().copied().filter(|&d| d > 0).fold(0u32, gcd);
+    let original_fps = if base_tick > 0 {
+        let candidate = (MS_PER_SECOND / base_tick as f64).round().max(1.0) as u32;
+        if (MIN_SANE_FPS..=MAX_SANE_FPS).contains(&candidate) {
+            candidate
+        } else {
+            average_fps
+        }
+    } else {
+        average_fps
+    };
+
+    let mut merged: Vec<(usize, u32)> = Vec::new();
+    for i in 0..frame_vec.len() {
+        if let Some((last_index, last_delay)) = merged.last_mut() {
+            if mean_channel_diff(&frame_vec[*last_index], &frame_vec[i]) <= DUPLICATE_FRAME_MEAN_DIFF_THRESHOLD {
+                *last_delay += delays[i];
+                continue;
+            }
+        }
+        merged.push((i, delays[i]));
+    }
+
+    (merged, original_fps)
+}
+
+/// sRGB electro-optical transfer function: decodes a normalized (0.0-1.0) sRGB-encoded
+/// channel value into linear light.
+fn srgb_to_linear(encoded: f32) -> f32 {
+    if encoded <= 0.04045 {
+        encoded / 12.92
+    } else {
+        ((encoded + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// sRGB opto-electronic transfer function: re-encodes a normalized (0.0-1.0) linear-light
+/// channel value back into sRGB gamma space.
+fn linear_to_srgb(linear: f32) -> f32 {
+    if linear <= 0.0031308 {
+        linear * 12.92
+    } else {
+        1.055 * linear.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Applies an ICC-style color-management pass to a frame before quantization: decodes
+/// each channel through the source tone-reproduction curve into linear light, applies a
+/// 3x3 working-space matrix, clamps to the target gamut, then re-encodes through the
+/// sRGB curve.
+///
+/// `image` doesn't expose an embedded color profile for GIF/WebP, so the content is taken
+/// to be sRGB already and returned as is, unless `source_gamma` sets the source curve to a
+/// simple parametric power curve, for content
+/// authored in another gamma (e.g. 1.8 or 2.2). The working-space matrix is the identity
+/// until a caller has chromaticity primaries to pass instead. Doing this in linear light
+/// matters because the 1-bit/4-bit grayscale and reduced-depth color packers quantize in
+/// the *encoded* domain, and deriving their luma/threshold decisions from correctly
+/// linearized brightness produces visibly better midtones.
+///
+/// # Arguments
+///
+/// * `image` - The frame to color-manage.
+/// * `source_gamma` - Parametric gamma override for the source curve (0.0 to assume the
+///   content is already sRGB-encoded).
+///
+/// # Returns
+///
+/// A new `DynamicImage`, re-encoded in sRGB gamma space. With the default `source_gamma`
+/// of 0.0 and the working-space matrix still at its identity default, this is a true
+/// no-op (the input is returned unchanged) rather than a decode/re-encode round trip,
+/// since that round trip perturbs pixels by a rounding unit even when mathematically
+/// it shouldn't change anything.
+pub fn apply_color_management(image: &DynamicImage, source_gamma: f32) -> DynamicImage {
+    const WORKING_SPACE_MATRIX: [[f32; 3]; 3] = [
+        [1.0, 0.0, 0.0],
+        [0.0, 1.0, 0.0],
+        [0.0, 0.0, 1.0],
+    ];
+
+    // WORKING_SPACE_MATRIX is always identity today (no caller has chromaticity primaries to
+    // pass yet), so source_gamma alone decides whether this pass would touch any pixel.
+    if source_gamma <= 0.0 {
+        return image.clone();
+    }
+
+    let decode = |encoded: u8| -> f32 {
+        let normalized = encoded as f32 / 255.0;
+        if source_gamma > 0.0 {
+            normalized.powf(source_gamma)
+        } else {
+            srgb_to_linear(normalized)
+        }
+    };
+
+    let mut rgb = image.to_rgb8();
+    for pixel in rgb.pixels_mut() {
+        let linear = [decode(pixel[0]), decode(pixel[1]), decode(pixel[2])];
+        for (channel, row) in pixel.0.iter_mut().zip(WORKING_SPACE_MATRIX.iter()) {
+            let transformed: f32 = row.iter().zip(linear.iter()).map(|(m, c)| m * c).sum();
+            *channel = (linear_to_srgb(transformed.clamp(0.0, 1.0)) * 255.0).round().clamp(0.0, 255.0) as u8;
+        }
+    }
+    DynamicImage::ImageRgb8(rgb)
+}
+
 /// Processes the image by decoding frames, sampling, resizing, and optionally converting to grayscale.
 ///
 /// # Arguments
@@ -42,6 +359,12 @@ pub fn get_frames(image_data: &[u8], image_type: &str) -> Result<Vec<Frame>, JsV
 /// * `max_size` - Maximum width/height for downscaling.
 /// * `target_fps` - Desired frames per second (limited by the original FPS).
 /// * `grayscale_bits` - Number of bits for grayscale conversion (0 means full color).
+/// * `rd_lambda` - Lagrange multiplier for rate-distortion frame selection (0 disables it).
+/// * `source_gamma` - Parametric gamma override for `apply_color_management` (0.0 to
+///   assume the content is already sRGB-encoded).
+/// * `use_gpu` - Whether to downscale and grayscale-quantize frames on the GPU via
+///   `crate::gpu` instead of the CPU path below. Falls back transparently to the CPU
+///   path when no `wgpu` adapter is available.
 ///
 /// # Returns
 ///
@@ -52,27 +375,24 @@ pub fn process_image(
     max_size: u32,
     target_fps: u32,
     grayscale_bits: u32,
+    rd_lambda: f32,
+    source_gamma: f32,
+    use_gpu: bool,
 ) -> Result<(Vec<DynamicImage>, u32), JsValue> {
-    // First pass: decode frames and gather durations.
+    // First pass: decode frames, detect the true base framerate, and collapse runs of
+    // visually-identical consecutive frames so the sampler below never wastes combinators
+    // on a repeated frame.
     let frame_vec = get_frames(image_data, image_type)?;
-    let mut durations = Vec::with_capacity(frame_vec.len());
-    let mut total_ms = 0u32;
-    for frame in &frame_vec {
-        let (ms, _) = frame.delay().numer_denom_ms();
-        let delay = if ms == 0 { DEFAULT_FRAME_DELAY_MS } else { ms };
-        durations.push(delay);
-        total_ms += delay;
-    }
-
-    // Compute average frame duration and derive FPS.
-    let avg_frame_duration = total_ms as f64 / frame_vec.len() as f64;
-    let original_fps = (MS_PER_SECOND / avg_frame_duration).floor() as u32;
+    let (merged, original_fps) = dedupe_and_detect_fps(&frame_vec);
+    let durations: Vec<u32> = merged.iter().map(|&(_, delay)| delay).collect();
+    let total_ms: u32 = durations.iter().sum();
+
     let effective_fps = target_fps.min(original_fps);
 
     // Determine target frame count.
     let target_total_frames = ((total_ms as f64 / 1000.0) * effective_fps as f64).round() as usize;
 
-    // Sample frames based on cumulative timing.
+    // Sample frames based on cumulative timing, indexing into the deduplicated list.
     let mut sampled_indices = Vec::with_capacity(target_total_frames);
     let mut next_target_time = 0.0;
     let mut accumulated_time = 0.0;
@@ -87,28 +407,233 @@ pub fn process_image(
         sampled_indices.push(0);
     }
 
-    // Second pass: process the sampled frames in parallel.
-    let processed: Vec<DynamicImage> = sampled_indices
+    // Second pass: color-manage the sampled frames in parallel. Downscaling (and, when
+    // requested, grayscale quantization) happens next, on the GPU if available.
+    let color_managed: Vec<DynamicImage> = sampled_indices
         .par_iter()
         .map(|&i| {
-            let frame = &frame_vec[i];
-            let mut img = DynamicImage::ImageRgba8(frame.clone().into_buffer());
+            let frame = &frame_vec[merged[i].0];
+            apply_color_management(&DynamicImage::ImageRgba8(frame.clone().into_buffer()), source_gamma)
+        })
+        .collect();
+
+    let gpu_reduced = if use_gpu {
+        crate::gpu::try_gpu_downscale_and_quantize(&color_managed, max_size, grayscale_bits)
+    } else {
+        None
+    };
+
+    let processed: Vec<DynamicImage> = match gpu_reduced {
+        Some(reduced) => reduced,
+        None => color_managed
+            .par_iter()
+            .map(|img| {
+                let mut img = img.clone();
+
+                // Convert to grayscale if requested.
+                if grayscale_bits > 0 {
+                    img = DynamicImage::ImageLuma8(img.to_luma8());
+                }
+
+                let (width, height) = img.dimensions();
+                let scale_factor = (max_size as f64 / width as f64)
+                    .min(max_size as f64 / height as f64)
+                    .min(1.0);
+                let new_width = (width as f64 * scale_factor).round() as u32;
+                let new_height = (height as f64 * scale_factor).round() as u32;
+                img.resize(new_width, new_height, FilterType::Triangle)
+            })
+            .collect(),
+    };
 
-            // Convert to grayscale if requested.
-            if grayscale_bits > 0 {
-                img = DynamicImage::ImageLuma8(img.to_luma8());
+    let processed = if rd_lambda > 0.0 {
+        rd_select_frames(processed, rd_lambda)
+    } else {
+        processed
+    };
+
+    Ok((processed, effective_fps))
+}
+
+/// Sum of squared per-channel error between two frames, in the `rgb_to_int` domain.
+///
+/// Used as the distortion term `D` for rate-distortion frame selection: the cost of
+/// displaying `held` instead of `dropped` for the duration `dropped` would have shown.
+fn frame_distortion(dropped: &DynamicImage, held: &DynamicImage) -> f64 {
+    let dropped_rgb = dropped.to_rgb8();
+    let held_rgb = held.to_rgb8();
+    dropped_rgb
+        .as_raw()
+        .iter()
+        .zip(held_rgb.as_raw().iter())
+        .map(|(&a, &b)| {
+            let diff = a as f64 - b as f64;
+            diff * diff
+        })
+        .sum()
+}
+
+/// Cheap proxy for the rate `R` a frame would cost: the number of pixels that differ
+/// from the previous frame, mirroring the changed-pixel count `generate_frame_combinators`
+/// would have to emit outputs for.
+fn frame_rate_estimate(previous: &DynamicImage, current: &DynamicImage) -> f64 {
+    let previous_rgb = previous.to_rgb8();
+    let current_rgb = current.to_rgb8();
+    previous_rgb
+        .pixels()
+        .zip(current_rgb.pixels())
+        .filter(|(a, b)| a != b)
+        .count() as f64
+}
+
+/// Greedily drops near-duplicate frames to minimize `D + lambda * R` (distortion plus
+/// a Lagrange-weighted rate), instead of the uniform FPS subsampling `process_image`
+/// otherwise relies on.
+///
+/// Starts from keeping every frame and repeatedly removes whichever currently-kept
+/// frame contributes the most negative `D - lambda * R` (i.e. the distortion from
+/// holding the previous kept frame instead is outweighed by the rate it would have
+/// cost), until no remaining frame is worth dropping. Frame 0 is never dropped since
+/// it anchors the loop.
+///
+/// # Arguments
+///
+/// * `frames` - Candidate frames, in display order.
+/// * `rd_lambda` - Lagrange multiplier trading distortion for rate; higher keeps fewer frames.
+///
+/// # Returns
+///
+/// The frames that survive, still in display order.
+fn rd_select_frames(frames: Vec<DynamicImage>, rd_lambda: f32) -> Vec<DynamicImage> {
+    if frames.len() <= 1 {
+        return frames;
+    }
+    let rd_lambda = rd_lambda as f64;
+    let mut kept = vec![true; frames.len()];
+
+    loop {
+        let mut best: Option<(usize, f64)> = None;
+        for i in 1..frames.len() {
+            if !kept[i] {
+                continue;
+            }
+            let Some(prev) = (0..i).rev().find(|&j| kept[j]) else {
+                continue;
+            };
+            let d = frame_distortion(&frames[i], &frames[prev]);
+            let r = frame_rate_estimate(&frames[prev], &frames[i]).max(1.0);
+            let net_cost = d - rd_lambda * r;
+            if best.map_or(true, |(_, best_cost)| net_cost < best_cost) {
+                best = Some((i, net_cost));
             }
+        }
+
+        match best {
+            Some((i, net_cost)) if net_cost < 0.0 => kept[i] = false,
+            _ => break,
+        }
+    }
+
+    frames
+        .into_iter()
+        .zip(kept)
+        .filter_map(|(frame, keep)| if keep { Some(frame) } else { None })
+        .collect()
+}
+
+/// The channel value of a sampled color, by index (0 = red, 1 = green, 2 = blue).
+fn channel_of(pixel: &(u8, u8, u8), channel: usize) -> u8 {
+    match channel {
+        0 => pixel.0,
+        1 => pixel.1,
+        _ => pixel.2,
+    }
+}
+
+/// Finds the channel with the widest value range across a slice of sampled colors,
+/// the split axis median-cut uses to divide the box that needs it most.
+///
+/// # Returns
+///
+/// `(channel, range)`, where `channel` is 0/1/2 for red/green/blue.
+fn widest_channel(samples: &[(u8, u8, u8)]) -> (usize, u32) {
+    let mut ranges = [0u32; 3];
+    for (channel, range) in ranges.iter_mut().enumerate() {
+        let mut min = 255u8;
+        let mut max = 0u8;
+        for sample in samples {
+            let value = channel_of(sample, channel);
+            min = min.min(value);
+            max = max.max(value);
+        }
+        *range = (max - min) as u32;
+    }
+    (0..3).map(|c| (c, ranges[c])).max_by_key(|&(_, r)| r).unwrap()
+}
+
+/// Builds a shared color palette via median-cut quantization, for indexed-palette color
+/// mode (`pack_palette_frames_to_outputs` in `blueprint`).
+///
+/// Starts with one box holding every sampled color and repeatedly splits the box with
+/// the largest channel range at its median along that channel, until `max_colors` boxes
+/// exist or no box has more than one distinct color left to split. Each final box's
+/// average color becomes one palette entry.
+///
+/// # Arguments
+///
+/// * `frames` - Representative frames to sample colors from (typically the full set of
+///   sampled frames, so the palette covers the whole animation).
+/// * `max_colors` - Target palette size (e.g. 16, 64, or 256, or a smaller cap).
+///
+/// # Returns
+///
+/// Up to `max_colors` `(r, g, b)` palette entries.
+pub fn build_palette(frames: &[DynamicImage], max_colors: u32) -> Vec<(u8, u8, u8)> {
+    let mut samples: Vec<(u8, u8, u8)> = Vec::new();
+    for frame in frames {
+        let rgb = frame.to_rgb8();
+        samples.extend(rgb.pixels().map(|p| (p[0], p[1], p[2])));
+    }
+    if samples.is_empty() {
+        return Vec::new();
+    }
 
-            let (width, height) = img.dimensions();
-            let scale_factor = (max_size as f64 / width as f64)
-                .min(max_size as f64 / height as f64)
-                .min(1.0);
-            let new_width = (width as f64 * scale_factor).round() as u32;
-            let new_height = (height as f64 * scale_factor).round() as u32;
-            img.resize(new_width, new_height, FilterType::Triangle)
+    let mut boxes: Vec<(usize, usize)> = vec![(0, samples.len())];
+    loop {
+        if boxes.len() >= max_colors as usize {
+            break;
+        }
+        let split = boxes
+            .iter()
+            .enumerate()
+            .filter(|(_, &(start, end))| end - start > 1)
+            .map(|(i, &(start, end))| (i, widest_channel(&samples[start..end])))
+            .max_by_key(|&(_, (_, range))| range);
+        let Some((box_index, (channel, range))) = split else {
+            break;
+        };
+        if range == 0 {
+            break;
+        }
+
+        let (start, end) = boxes[box_index];
+        samples[start..end].sort_by_key(|p| channel_of(p, channel));
+        let mid = start + (end - start) / 2;
+        boxes[box_index] = (start, mid);
+        boxes.insert(box_index + 1, (mid, end));
+    }
+
+    boxes
+        .into_iter()
+        .map(|(start, end)| {
+            let count = (end - start) as u32;
+            let (r_sum, g_sum, b_sum) = samples[start..end].iter().fold(
+                (0u32, 0u32, 0u32),
+                |(r, g, b), &(pr, pg, pb)| (r + pr as u32, g + pg as u32, b + pb as u32),
+            );
+            ((r_sum / count) as u8, (g_sum / count) as u8, (b_sum / count) as u8)
         })
-        .collect();
-    Ok((processed, effective_fps))
+        .collect()
 }
 
 /// Converts an RGB pixel to a single 24 bit integer (inside a u32, I know...).
@@ -125,3 +650,19 @@ pub fn process_image(
 pub fn rgb_to_int(r: u8, g: u8, b: u8) -> u32 {
     ((r as u32) << 16) | ((g as u32) << 8) | (b as u32)
 }
+
+/// Converts a whole frame's interleaved RGB bytes into `rgb_to_int` values in one pass.
+///
+/// A ragged tail (byte count not a multiple of 3) is dropped, as `chunks_exact(3)` drops
+/// a trailing partial pixel.
+///
+/// # Arguments
+///
+/// * `pixels` - Interleaved RGB bytes (e.g. `RgbImage::into_raw`/`as_raw`).
+///
+/// # Returns
+///
+/// One packed `u32` per whole pixel, in the same order as the input.
+pub fn rgb_bytes_to_ints(pixels: &[u8]) -> Vec<u32> {
+    pixels.chunks_exact(3).map(|p| rgb_to_int(p[0], p[1], p[2])).collect()
+}
@@ -1,4 +1,5 @@
 use wasm_bindgen::prelude::*;
+use image::GenericImageView;
 
 mod progress;
 mod image_processing;
@@ -6,18 +7,48 @@ mod blueprint;
 mod signals;
 mod constants;
 mod models;
+mod rate_control;
+mod gpu;
 
 /// Public entry point for WebAssembly.
 ///
 /// # Parameters
 ///
 /// - `image_data`: Byte array containing the GIF/WebP data.
-/// - `image_type`: Type of the image ("gif" or "webp").
+/// - `image_type`: Type of the image ("gif", "webp", "mp4", or "webm").
 /// - `use_dlc`: Whether to use additional DLC signals.
 /// - `target_fps`: Desired frames per second (won't exceed original FPS).
 /// - `max_size`: Maximum dimension (width/height) for downscaling.
 /// - `substation_quality`: Quality of substations to use.
 /// - `grayscale_bits`: Number of bits for grayscale conversion (0 means full color).
+/// - `delta_encode`: Whether to residual-encode frames through a self-feeding accumulator
+///   to cut combinator count on mostly-static animations. Only supported in plain
+///   grayscale/full-color mode; combining it with `color_bits`, `ycbcr_bits`, or
+///   `palette_bits` returns an error.
+/// - `delta_keyframe_interval`: Force a keyframe every N frames when `delta_encode` is on
+///   (0 to rely solely on the internal wire-budget cap).
+/// - `delta_threshold`: Per-channel change threshold at or below which a delta-encoded pixel is
+///   left at its last value instead of being updated (0 keeps only exact matches).
+/// - `rd_lambda`: Lagrange multiplier for rate-distortion frame selection (0 disables it).
+/// - `color_bits`: Total bits per packed color pixel for reduced-depth color mode (8/12/16
+///   for RGB332/444/565, 0 for full color). Ignored when `grayscale_bits` is non-zero.
+/// - `ycbcr_bits`: Bits per Y/Cb/Cr sample for YCbCr 4:2:0 chroma-subsampled color mode
+///   (0 disables it, 1/4/8 are the only other accepted values). Takes priority over
+///   `color_bits` when `grayscale_bits` is also 0.
+/// - `dither_mode`: 0 disables dithering, 1 selects Floyd–Steinberg error diffusion, 2
+///   selects ordered (Bayer matrix) dithering, for the 1-bit/4-bit grayscale and
+///   reduced-depth color paths instead of quantizing each pixel independently. Ordered
+///   dithering tiles identically across frames, which suits looping content better.
+/// - `palette_bits`: Bits per packed index for indexed-palette color mode (4/6/8 for a
+///   16/64/256-color palette, 0 disables it). Only used when `grayscale_bits`, `ycbcr_bits`,
+///   and `color_bits` are all 0.
+/// - `max_colors`: Caps the shared palette's actual color count below what `palette_bits`
+///   allows (0 to use the full `1 << palette_bits` palette). Only used in palette mode.
+/// - `source_gamma`: Parametric gamma override for the source color-management curve
+///   applied before quantization (0.0 to assume the content is already sRGB-encoded).
+/// - `use_gpu`: Whether to downscale and grayscale-quantize frames on the GPU via
+///   `wgpu` instead of the CPU path. Falls back transparently to the CPU path when no
+///   adapter is available.
 ///
 /// # Returns
 ///
@@ -31,17 +62,120 @@ pub fn run_blueprint(
     max_size: u32,
     substation_quality: String,
     grayscale_bits: u32,
+    delta_encode: bool,
+    delta_keyframe_interval: u32,
+    delta_threshold: u8,
+    rd_lambda: f32,
+    color_bits: u32,
+    ycbcr_bits: u32,
+    dither_mode: u32,
+    palette_bits: u32,
+    max_colors: u32,
+    source_gamma: f32,
+    use_gpu: bool,
 ) -> Result<String, JsValue> {
     // Process the image to extract frames and determine the effective FPS.
-    let (frames, fps) = image_processing::process_image(image_data, image_type, max_size, target_fps, grayscale_bits)?;
+    let (frames, fps) = image_processing::process_image(image_data, image_type, max_size, target_fps, grayscale_bits, rd_lambda, source_gamma, use_gpu)?;
     if frames.is_empty() {
         return Err(JsValue::from_str("No frames sampled!"));
     }
 
     // Build the complete blueprint JSON.
-    let blueprint_json = blueprint::update_full_blueprint(fps, frames, use_dlc, grayscale_bits, substation_quality)?;
+    let blueprint_json = blueprint::update_full_blueprint(fps, frames, use_dlc, grayscale_bits, substation_quality, delta_encode, delta_keyframe_interval, delta_threshold, color_bits, ycbcr_bits, dither_mode, palette_bits, max_colors)?;
 
     // Encode the blueprint into a Factorio blueprint string.
     let blueprint_str = blueprint::encode_blueprint(&blueprint_json)?;
     Ok(blueprint_str)
 }
+
+/// Public entry point for WebAssembly that targets a fixed entity budget instead of
+/// fixed encode parameters.
+///
+/// Runs a cheap first pass to estimate the entity count for candidate parameters,
+/// adjusting fps, resolution, and grayscale bits until the estimate fits under
+/// `target_entities`, then encodes for real with the chosen parameters.
+///
+/// # Parameters
+///
+/// - `image_data`: Byte array containing the GIF/WebP data.
+/// - `image_type`: Type of the image ("gif", "webp", "mp4", or "webm").
+/// - `use_dlc`: Whether to use additional DLC signals.
+/// - `substation_quality`: Quality of substations to use.
+/// - `delta_encode`: Whether to residual-encode frames through a self-feeding accumulator.
+///   Only supported in plain grayscale/full-color mode; combining it with `color_bits`,
+///   `ycbcr_bits`, or `palette_bits` returns an error.
+/// - `delta_keyframe_interval`: Force a keyframe every N frames when `delta_encode` is on
+///   (0 to rely solely on the internal wire-budget cap).
+/// - `delta_threshold`: Per-channel change threshold at or below which a delta-encoded pixel is
+///   left at its last value instead of being updated (0 keeps only exact matches).
+/// - `rd_lambda`: Lagrange multiplier for rate-distortion frame selection (0 disables it).
+/// - `target_entities`: Maximum number of entities the blueprint should contain.
+/// - `color_bits`: Total bits per packed color pixel for reduced-depth color mode (8/12/16
+///   for RGB332/444/565, 0 for full color). Ignored when the budget search lands on grayscale.
+/// - `ycbcr_bits`: Bits per Y/Cb/Cr sample for YCbCr 4:2:0 chroma-subsampled color mode
+///   (0 disables it, 1/4/8 are the only other accepted values). Takes priority over
+///   `color_bits` when the budget search lands on color.
+/// - `dither_mode`: 0 disables dithering, 1 selects Floyd–Steinberg error diffusion, 2
+///   selects ordered (Bayer matrix) dithering, for the 1-bit/4-bit grayscale and
+///   reduced-depth color paths instead of quantizing each pixel independently. Ordered
+///   dithering tiles identically across frames, which suits looping content better.
+/// - `palette_bits`: Bits per packed index for indexed-palette color mode (4/6/8 for a
+///   16/64/256-color palette, 0 disables it). Only used when the budget search lands on
+///   color and `color_bits`/`ycbcr_bits` are both 0.
+/// - `max_colors`: Caps the shared palette's actual color count below what `palette_bits`
+///   allows (0 to use the full `1 << palette_bits` palette). Only used in palette mode.
+/// - `source_gamma`: Parametric gamma override for the source color-management curve
+///   applied before quantization (0.0 to assume the content is already sRGB-encoded).
+/// - `use_gpu`: Whether to downscale and grayscale-quantize frames on the GPU via
+///   `wgpu` instead of the CPU path. Falls back transparently to the CPU path when no
+///   adapter is available.
+///
+/// # Returns
+///
+/// A JSON string with the chosen parameters and the resulting blueprint string.
+#[wasm_bindgen]
+pub fn run_blueprint_with_budget(
+    image_data: &[u8],
+    image_type: &str,
+    use_dlc: bool,
+    substation_quality: String,
+    delta_encode: bool,
+    delta_keyframe_interval: u32,
+    delta_threshold: u8,
+    rd_lambda: f32,
+    target_entities: u32,
+    color_bits: u32,
+    ycbcr_bits: u32,
+    dither_mode: u32,
+    palette_bits: u32,
+    max_colors: u32,
+    source_gamma: f32,
+    use_gpu: bool,
+) -> Result<String, JsValue> {
+    // Pass one: probe timing/dimensions and search for parameters that fit the budget.
+    let (width, height, total_duration_ms, original_fps) = image_processing::probe_animation(image_data, image_type)?;
+    let num_signals = signals::get_signals_with_quality(use_dlc).len() as u32;
+    let chosen = rate_control::fit_to_budget(original_fps, width, height, total_duration_ms, target_entities, num_signals);
+
+    // Pass two: encode for real with the chosen parameters.
+    let (frames, fps) = image_processing::process_image(image_data, image_type, chosen.max_size, chosen.fps, chosen.grayscale_bits, rd_lambda, source_gamma, use_gpu)?;
+    if frames.is_empty() {
+        return Err(JsValue::from_str("No frames sampled!"));
+    }
+    let total_frames = frames.len() as u32;
+    let (encoded_width, encoded_height) = frames[0].dimensions();
+    let blueprint_json = blueprint::update_full_blueprint(fps, frames, use_dlc, chosen.grayscale_bits, substation_quality, delta_encode, delta_keyframe_interval, delta_threshold, color_bits, ycbcr_bits, dither_mode, palette_bits, max_colors)?;
+    let blueprint_str = blueprint::encode_blueprint(&blueprint_json)?;
+
+    let result = rate_control::ChosenParams {
+        fps,
+        max_size: chosen.max_size,
+        grayscale_bits: chosen.grayscale_bits,
+        estimated_entities: rate_control::estimate_entity_count(encoded_width, encoded_height, total_frames, chosen.grayscale_bits, num_signals),
+    };
+    serde_json::to_string(&serde_json::json!({
+        "blueprint": blueprint_str,
+        "params": result,
+    }))
+    .map_err(|e| JsValue::from_str(&format!("Serialization error: {}", e)))
+}
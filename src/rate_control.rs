@@ -0,0 +1,125 @@
+use serde::Serialize;
+
+/// Parameters the rate controller is allowed to adjust to hit an entity budget.
+#[derive(Clone, Copy, Debug)]
+pub struct EncodeParams {
+    pub fps: u32,
+    pub max_size: u32,
+    pub grayscale_bits: u32,
+}
+
+/// Chosen parameters returned to the caller alongside the blueprint, so it can
+/// report what was traded away to hit the requested budget.
+#[derive(Serialize)]
+pub struct ChosenParams {
+    pub fps: u32,
+    pub max_size: u32,
+    pub grayscale_bits: u32,
+    pub estimated_entities: u32,
+}
+
+/// Cheap closed-form estimate of the final entity count for a given set of parameters.
+///
+/// Mirrors the column/row math in `update_full_blueprint` and the per-group decider
+/// count from `generate_frame_combinators`/`generate_substations`, without actually
+/// building any entities, so it's cheap enough to call many times during search.
+///
+/// # Arguments
+///
+/// * `full_width` - Lamp grid width after downscaling.
+/// * `full_height` - Lamp grid height after downscaling.
+/// * `total_frames` - Number of sampled frames.
+/// * `grayscale_bits` - Number of grayscale bits (0 means full color).
+/// * `num_signals` - Number of available signals (bounds columns per group).
+///
+/// # Returns
+///
+/// An estimated entity count, including lamps, deciders, and substations.
+pub fn estimate_entity_count(
+    full_width: u32,
+    full_height: u32,
+    total_frames: u32,
+    grayscale_bits: u32,
+    num_signals: u32,
+) -> u32 {
+    if full_width == 0 || full_height == 0 || total_frames == 0 {
+        return 0;
+    }
+    let frames_per_combinator = if grayscale_bits > 0 { (32 / grayscale_bits).max(1) } else { 1 };
+    let max_columns_per_group = (num_signals / full_height).min(full_width).max(1);
+    let num_groups = (full_width as f64 / max_columns_per_group as f64).ceil() as u32;
+    let deciders_per_group = (total_frames as f64 / frames_per_combinator as f64).ceil() as u32;
+    let shifters_per_group = if grayscale_bits > 0 { 3 } else { 0 };
+
+    let lamp_count = full_width * full_height;
+    let decider_count = num_groups * (deciders_per_group * 2 + shifters_per_group);
+    // Coverage of a normal-quality substation; good enough for an estimate since the
+    // caller only needs an order-of-magnitude budget check, not exact placement.
+    let coverage = 18u32;
+    let substation_count = ((full_width / coverage) + 1) * ((full_height / coverage) + 1 + (total_frames / coverage));
+
+    lamp_count + decider_count + substation_count + 6
+}
+
+/// Iteratively adjusts fps, resolution, and grayscale bits until the estimated entity
+/// count fits under `target_entities`, preferring to drop fps before resolution.
+///
+/// # Arguments
+///
+/// * `original_fps` - FPS of the source animation (upper bound on `fps`).
+/// * `original_width` / `original_height` - Source dimensions before downscaling.
+/// * `total_duration_ms` - Total animation duration, used to re-derive frame count per fps.
+/// * `target_entities` - Entity budget to fit under.
+/// * `num_signals` - Number of available signals (bounds columns per group).
+///
+/// # Returns
+///
+/// The chosen parameters, which may still exceed the budget if even the smallest
+/// settings don't fit.
+pub fn fit_to_budget(
+    original_fps: u32,
+    original_width: u32,
+    original_height: u32,
+    total_duration_ms: u32,
+    target_entities: u32,
+    num_signals: u32,
+) -> EncodeParams {
+    let mut fps = original_fps.max(1);
+    let mut max_size = original_width.max(original_height).max(1);
+    let mut grayscale_bits = 0u32;
+
+    loop {
+        let scale = (max_size as f64 / original_width as f64).min(max_size as f64 / original_height as f64).min(1.0);
+        let width = (original_width as f64 * scale).round().max(1.0) as u32;
+        let height = (original_height as f64 * scale).round().max(1.0) as u32;
+        let total_frames = (((total_duration_ms as f64 / 1000.0) * fps as f64).round() as u32).max(1);
+        let estimate = estimate_entity_count(width, height, total_frames, grayscale_bits, num_signals);
+
+        if estimate <= target_entities {
+            break;
+        }
+
+        // Preserve spatial resolution by default: drop fps first.
+        if fps > 1 {
+            fps -= (fps / 4).max(1);
+            continue;
+        }
+        if max_size > 8 {
+            max_size -= (max_size / 8).max(1);
+            continue;
+        }
+        if grayscale_bits == 0 {
+            grayscale_bits = 8;
+            continue;
+        }
+        if grayscale_bits > 1 {
+            // The grayscale packers only decode 8, 4, and 1 bits per sample.
+            grayscale_bits = if grayscale_bits == 8 { 4 } else { 1 };
+            continue;
+        }
+        // Nothing left to trade away; report the smallest settings we have.
+        break;
+    }
+
+    EncodeParams { fps, max_size, grayscale_bits }
+}